@@ -0,0 +1,237 @@
+//! Attack module
+//! Search the choices of an attacker to find the attack delaying the game most per unit of
+//! slashed bond, and whether the attacker can profit from any attack.
+//!
+//! In relayers-only mode the attacker is a relayer and the choices are made of `H` and `L`, as `N`
+//! is submitted as `H`, in relayer-challenger(s) mode the attacker is a challenger and the choices are made of `0` and
+//! `1`.  The delay is the Darwinia blocks of the game more than the game without the attacker.
+//! The choices of the relayer start with a lie to open the dispute and end with a lie, because the
+//! rounds before the dispute and after the last lie are not caused by the attacker.
+use std::fmt;
+
+use crate::error::Error;
+use crate::game::run;
+use crate::scenario::ScenarioConfig;
+
+/// # Attack
+/// The outcome of a choice of the attacker
+#[derive(Debug, Clone)]
+pub struct Attack {
+    pub choice: String,
+    /// The Darwinia blocks of the game
+    pub duration: usize,
+    /// The Darwinia blocks of the game more than the one without the attacker
+    pub delay: usize,
+    /// The bond of the attacker slashed in the game
+    pub slashed: f64,
+    /// The balance of the attacker earned in the game
+    pub balance: f64,
+}
+
+impl Attack {
+    /// The delay per unit of slashed bond, it is infinite if the delay costs nothing
+    pub fn delay_per_slash(&self) -> f64 {
+        if self.slashed > 0.0 {
+            self.delay as f64 / self.slashed
+        } else if self.delay > 0 {
+            f64::INFINITY
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Display for Attack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}, Delay: {} blocks, Slashed: {}, Delay per Slashed Bond: {}, Balance: {}",
+            self.choice,
+            self.delay,
+            self.slashed,
+            self.delay_per_slash(),
+            self.balance
+        )
+    }
+}
+
+/// # Analysis
+/// The attacks searched for the attacker
+#[derive(Debug)]
+pub struct Analysis {
+    pub attacker: String,
+    /// The max length of the choices searched
+    pub length: usize,
+    /// The game without the attacker
+    pub baseline: Attack,
+    pub attacks: Vec<Attack>,
+}
+
+impl Analysis {
+    /// The attack delaying the game most per unit of slashed bond, the longer delay is preferred
+    /// if they are the same
+    pub fn best(&self) -> Option<&Attack> {
+        self.attacks.iter().filter(|a| a.delay > 0).max_by(|a, b| {
+            (a.delay_per_slash(), a.delay)
+                .partial_cmp(&(b.delay_per_slash(), b.delay))
+                .unwrap()
+        })
+    }
+    /// The attacks with positive balance
+    pub fn profitable(&self) -> Vec<&Attack> {
+        self.attacks.iter().filter(|a| a.balance > 0.0).collect()
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Attacker: {}, Choices: {} (length 1 to {})",
+            self.attacker,
+            self.attacks.len(),
+            self.length
+        )?;
+        writeln!(
+            f,
+            "Duration without Attacker: {} blocks",
+            self.baseline.duration
+        )?;
+        match self.best() {
+            Some(a) => writeln!(f, "Best Attack: {}", a)?,
+            None => writeln!(f, "Best Attack: none of the choices delays the game")?,
+        }
+        let profitable = self.profitable();
+        match profitable
+            .iter()
+            .max_by(|a, b| a.balance.partial_cmp(&b.balance).unwrap())
+        {
+            Some(a) => write!(
+                f,
+                "Profitable Attacks: {}, the most profitable: {}",
+                profitable.len(),
+                a
+            ),
+            None => write!(f, "Profitable Attacks: none"),
+        }
+    }
+}
+
+/// Play the game with the choice of the attacker
+fn attack(config: &ScenarioConfig, attacker: &str, choice: &str) -> Result<Attack, Error> {
+    let mut config = config.clone();
+    config.set_choice(attacker, choice)?;
+    let (chains_status, result) = run(&config)?;
+    let p = chains_status
+        .relayers
+        .get(attacker)
+        .or_else(|| chains_status.challengers.get(attacker))
+        .ok_or(Error::ParameterError("the attacker is not in the scenario"))?;
+    Ok(Attack {
+        choice: choice.to_string(),
+        duration: result.duration,
+        delay: 0,
+        slashed: (p.pay - p.reward.0).max(0.0),
+        balance: p.balance() - p.initial_balance,
+    })
+}
+
+/// Search the choices of the attacker up to the length, the attacker is default the first
+/// relayer in relayers-only mode, or the first challenger in relayer-challenger(s) mode
+pub fn analyse(
+    config: &ScenarioConfig,
+    attacker: Option<&str>,
+    length: usize,
+) -> Result<Analysis, Error> {
+    if config.games.is_some() {
+        return Err(Error::ParameterError(
+            "Concurrent games can not be analysed",
+        ));
+    }
+    if length == 0 {
+        return Err(Error::ParameterError(
+            "the length of choices should be positive",
+        ));
+    }
+    let (alphabet, length, default_attacker) = match &config.challengers {
+        Some(challengers) => (
+            "01",
+            length.min(config.relayers[0].choice.len()),
+            challengers.first(),
+        ),
+        None => ("HL", length, config.relayers.get(1)),
+    };
+    let attacker = match attacker {
+        Some(a) => a.to_string(),
+        None => default_attacker
+            .map(|p| p.id().to_string())
+            .ok_or(Error::ParameterError(
+                "there is no attacker in the scenario",
+            ))?,
+    };
+
+    let baseline = attack(config, &attacker, "")?;
+    let mut attacks = Vec::new();
+    let mut choices = vec![String::new()];
+    for _ in 0..length {
+        choices = choices
+            .iter()
+            .flat_map(|c| alphabet.chars().map(move |a| format!("{}{}", c, a)))
+            .collect();
+        for choice in choices.iter() {
+            if alphabet == "HL" && !(choice.starts_with('L') && choice.ends_with('L')) {
+                continue;
+            }
+            let mut a = attack(config, &attacker, choice)?;
+            a.delay = a.duration.saturating_sub(baseline.duration);
+            attacks.push(a);
+        }
+    }
+    Ok(Analysis {
+        attacker,
+        length,
+        baseline,
+        attacks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn test_analyse() {
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10.0"
+			reward_function = "split"
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "L"
+			"#,
+        )
+        .unwrap();
+        let analysis = analyse(&config, None, 3).unwrap();
+        assert_eq!(analysis.attacker, "Evil");
+        assert_eq!(analysis.attacks.len(), 1 + 1 + 2);
+        assert_eq!(analysis.baseline.duration, 10);
+
+        // each round takes 10 blocks and the bond of each lie is 10
+        let best = analysis.best().unwrap();
+        assert_eq!(
+            (best.choice.as_str(), best.delay, best.slashed),
+            ("LLL", 30, 30.0)
+        );
+        assert_eq!(best.delay_per_slash(), 1.0);
+        assert!(analysis.profitable().is_empty());
+
+        assert!(analyse(&config, Some("Nobody"), 3).is_err());
+        assert!(analyse(&config, None, 0).is_err());
+    }
+}
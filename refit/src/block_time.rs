@@ -0,0 +1,217 @@
+//! Block Time module
+//! The block producing of Darwinia and Ethereum are not in constant speed, for example, the block
+//! producing of Ethereum is Poisson-like.  This module simulate the block time with different
+//! models, such that the duration and the challenge windows can reflect the variance.
+//! The same models are used for the latency of participators.
+//!
+//! surfix d: the parameters for Darwinia
+//! surfix e: the parameters for target chain (for example Ethereum)
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// # Block Time Config
+/// The model of block time can be `fixed`, `exponential` or `empirical`
+/// - fixed: each block time is the mean block time T
+/// - exponential: the block time is exponential distribution with mean block time T
+/// - empirical: the block time is randomly picked from the samples S
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BlockTimeConfig {
+    /// The block time model of Darwinia
+    pub darwinia: String,
+    /// The block time model of Ethereum
+    pub ethereum: String,
+    /// Td: the mean block time of Darwinia in seconds
+    Td: Option<f64>,
+    /// Te: the mean block time of Ethereum in seconds
+    Te: Option<f64>,
+    /// Sd: the samples of Darwinia block time in seconds for empirical model
+    Sd: Option<Vec<f64>>,
+    /// Se: the samples of Ethereum block time in seconds for empirical model
+    Se: Option<Vec<f64>>,
+}
+
+impl BlockTimeConfig {
+    pub fn validate(&self) -> Result<(), Error> {
+        Distribution::new(&self.darwinia, self.Td, self.Sd.clone())
+            .map_err(|e| e.in_table("darwinia"))?;
+        Distribution::new(&self.ethereum, self.Te, self.Se.clone())
+            .map_err(|e| e.in_table("ethereum"))?;
+        Ok(())
+    }
+}
+
+/// # Latency Config
+/// The latency of the submissions or challenges from a participator in Darwinia blocks, the model
+/// can be `fixed`, `exponential` or `empirical` as the block time model
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LatencyConfig {
+    pub model: String,
+    /// The mean latency in Darwinia blocks
+    pub mean: Option<f64>,
+    /// The samples of latency in Darwinia blocks for empirical model
+    pub samples: Option<Vec<f64>>,
+}
+
+impl LatencyConfig {
+    pub fn validate(&self) -> Result<(), Error> {
+        self.get_distribution()?;
+        Ok(())
+    }
+    pub fn get_distribution(&self) -> Result<Distribution, Error> {
+        Distribution::new(&self.model, self.mean, self.samples.clone())
+    }
+}
+
+/// # Distribution
+/// The distribution of time, used for block time and the latency of participators
+#[derive(Debug, Clone)]
+pub enum Distribution {
+    Fixed(f64),
+    Exponential(f64),
+    Empirical(Vec<f64>),
+}
+
+impl Distribution {
+    pub fn new(model: &str, mean: Option<f64>, samples: Option<Vec<f64>>) -> Result<Self, Error> {
+        match model.to_uppercase().as_str() {
+            "FIXED" | "EXPONENTIAL" => {
+                let t = mean.ok_or(Error::ParameterError(
+                    "lack mean time for specified time model",
+                ))?;
+                if t <= 0.0 {
+                    return Err(Error::ParameterError("mean time should be positive"));
+                }
+                if model.to_uppercase() == "FIXED" {
+                    Ok(Distribution::Fixed(t))
+                } else {
+                    Ok(Distribution::Exponential(t))
+                }
+            }
+            "EMPIRICAL" => {
+                let s = samples.unwrap_or_default();
+                if s.is_empty() {
+                    return Err(Error::ParameterError(
+                        "lack samples for empirical time model",
+                    ));
+                }
+                if s.iter().any(|t| *t < 0.0) {
+                    return Err(Error::ParameterError("time should not be negative"));
+                }
+                Ok(Distribution::Empirical(s))
+            }
+            _ => Err(Error::ParameterError("Time model not support")),
+        }
+    }
+    pub fn sample(&self, rng: &mut StdRng) -> f64 {
+        match self {
+            Distribution::Fixed(t) => *t,
+            Distribution::Exponential(t) => -t * (1.0 - rng.gen::<f64>()).ln(),
+            Distribution::Empirical(s) => s[rng.gen_range(0, s.len())],
+        }
+    }
+}
+
+/// # Block Producer
+/// Produce the blocks of Darwinia and Ethereum with the block time models
+#[derive(Debug, Clone)]
+pub struct BlockProducer {
+    rng: StdRng,
+    darwinia: Distribution,
+    ethereum: Distribution,
+    /// The time to the next Ethereum block
+    ethereum_countdown: f64,
+}
+
+impl BlockProducer {
+    /// The block producer with the random numbers from the `seed` of the scenario
+    pub fn new(c: BlockTimeConfig, seed: u64) -> Self {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let darwinia = Distribution::new(&c.darwinia, c.Td, c.Sd).unwrap();
+        let ethereum = Distribution::new(&c.ethereum, c.Te, c.Se).unwrap();
+        let ethereum_countdown = ethereum.sample(&mut rng);
+        BlockProducer {
+            rng,
+            darwinia,
+            ethereum,
+            ethereum_countdown,
+        }
+    }
+    /// Produce the Darwinia blocks, return the time in seconds and the Ethereum blocks produced
+    /// in the same time
+    pub fn produce(&mut self, darwinia_blocks: usize) -> (f64, usize) {
+        let mut time = 0f64;
+        for _ in 0..darwinia_blocks {
+            time += self.darwinia.sample(&mut self.rng);
+        }
+        let mut remain = time;
+        let mut ethereum_blocks = 0;
+        while self.ethereum_countdown <= remain {
+            remain -= self.ethereum_countdown;
+            ethereum_blocks += 1;
+            self.ethereum_countdown = self.ethereum.sample(&mut self.rng);
+        }
+        self.ethereum_countdown -= remain;
+        (time, ethereum_blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn config(darwinia: &str, ethereum: &str) -> BlockTimeConfig {
+        BlockTimeConfig {
+            darwinia: darwinia.to_string(),
+            ethereum: ethereum.to_string(),
+            Td: Some(6.0),
+            Te: Some(12.0),
+            Sd: None,
+            Se: Some(vec![10.0, 14.0]),
+        }
+    }
+    #[test]
+    fn test_fixed_block_time() {
+        let mut p = BlockProducer::new(config("fixed", "fixed"), 7);
+        assert_eq!(p.produce(10), (60.0, 5));
+        assert_eq!(p.produce(1), (6.0, 0));
+        assert_eq!(p.produce(1), (6.0, 1));
+    }
+    #[test]
+    fn test_random_block_time_with_seed() {
+        let mut p = BlockProducer::new(config("exponential", "empirical"), 7);
+        let mut q = BlockProducer::new(config("exponential", "empirical"), 7);
+        let (time, blocks) = p.produce(1000);
+        assert_eq!(q.produce(1000), (time, blocks));
+        assert!(time > 5000.0 && time < 7000.0);
+        assert!(blocks > 400 && blocks < 600);
+    }
+    #[test]
+    fn test_latency() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let l = LatencyConfig {
+            model: "empirical".to_string(),
+            mean: None,
+            samples: Some(vec![3.0, 5.0]),
+        };
+        let d = l.get_distribution().unwrap();
+        for _ in 0..10 {
+            let t = d.sample(&mut rng);
+            assert!(t == 3.0 || t == 5.0);
+        }
+        let l = LatencyConfig {
+            model: "exponential".to_string(),
+            mean: None,
+            samples: None,
+        };
+        assert!(l.validate().is_err());
+    }
+    #[test]
+    fn test_validate() {
+        assert!(config("fixed", "empirical").validate().is_ok());
+        assert!(config("empirical", "fixed").validate().is_err());
+        assert!(config("poisson", "fixed").validate().is_err());
+    }
+}
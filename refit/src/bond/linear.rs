@@ -1,13 +1,13 @@
 //! Linear Equation for the bond function
 use crate::bond::{ConfigValidate, Equation};
 use crate::error::Error;
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// # Linear bond equation
 /// Here is the linear equation  
 /// bond value of submit = min(W * B, M)) + C
 #[allow(non_snake_case)]
-#[derive(Default, Debug, Deserialize, Copy, Clone)]
+#[derive(Default, Debug, Deserialize, Serialize, Copy, Clone)]
 pub struct LinearConfig {
     /// W: the weights of submit times
     W: f64,
@@ -20,20 +20,7 @@ pub struct LinearConfig {
 impl ConfigValidate for LinearConfig {
     fn validate(&self) -> Result<(), Error> {
         if self.W < 0.0 {
-            return Err(Error::ParameterError("W should not be negative"));
-        }
-        Ok(())
-    }
-    fn apply_patch(&mut self, k: &str, v: &str) -> Result<(), Error> {
-        match k {
-            "C" => self.C = v.parse::<f64>()?,
-            "W" => self.W = v.parse::<f64>()?,
-            "M" => self.M = v.parse::<f64>()?,
-            _ => {
-                return Err(Error::PatchParameterError(
-                    "parameter not correct".to_string(),
-                ))
-            }
+            return Err(Error::field("W", "should not be negative"));
         }
         Ok(())
     }
@@ -50,18 +37,3 @@ impl Equation for LinearConfig {
         }
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_apply_patch() {
-        let mut c = LinearConfig::default();
-        c.apply_patch("C", "9.9").unwrap();
-        c.apply_patch("W", "1.234").unwrap();
-        c.apply_patch("M", "8.8").unwrap();
-        assert_eq!(c.C, 9.9);
-        assert_eq!(c.W, 1.234);
-        assert_eq!(c.M, 8.8);
-    }
-}
@@ -1,12 +1,20 @@
 ///! Simulation chain
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::mem::swap;
 
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use crate::block_time::{BlockProducer, Distribution};
+use crate::cost::TransactionCostConfig;
+use crate::error::Error;
+use crate::header::{validate, Header, HeaderChain};
 use crate::sample::Equation;
 use crate::scenario::{ChallengerConfig, RelayerConfig, ScenarioConfig};
+use crate::strategy::Observation;
+use crate::treasury::Treasury;
 
-static TOTAL_RELAYER: AtomicUsize = AtomicUsize::new(0);
 static VISUALIZED_MAX_LENGTH: usize = 64;
 
 /// # RewardFrom
@@ -14,9 +22,9 @@ static VISUALIZED_MAX_LENGTH: usize = 64;
 /// In somecase, there is no evil relayer in the submitround, so the relayer may be reward by
 /// treasury.  However, in production, the treasury reward part may be some points.
 /// The user will pay a fee to treasury in redeem action, and then the relayer get the
-/// reward from the fee accorance with the share of powint
+/// reward from the fee accorance with the share of powint, this is simulated in `treasury` module
 ///
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum RewardFrom {
     Treasure,
     Slash,
@@ -24,7 +32,7 @@ pub enum RewardFrom {
 
 /// # Reward
 /// This is the action structure for pay reward to someone
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Reward {
     /// The reward value from slash or treasury
     pub from: RewardFrom,
@@ -38,7 +46,7 @@ pub struct Reward {
 /// simulate  the status both Darwinia and Ethereum
 /// The status of challenger and relauer are the same,
 /// the only different is their behavior
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct ChainsStatus {
     /// The current block height of Darwinia
     pub darwinia_block_hight: usize,
@@ -52,13 +60,58 @@ pub struct ChainsStatus {
     pub submit_target_ethereum_block: usize,
     /// The list of submission
     pub submitions: Vec<(usize, usize)>,
+    /// The last relayed block on Darwinia and Ethereum before the game start
+    pub genesis: (usize, usize),
+    /// The synthetic header chain of Ethereum, only simulated with header config
+    pub header_chain: Option<HeaderChain>,
+    /// The headers submitted in the game and the relayers submitted them, keyed by block number
+    pub submit_headers: BTreeMap<usize, Vec<(Header, Vec<String>)>>,
     /// The factor for the block producing speed
     pub block_speed_factor: f64,
+    /// The block producer with block time models, the block speed factor is not used if it is set
+    pub block_producer: Option<BlockProducer>,
+    /// The time in seconds elapsed in the game, only simulated with block producer
+    pub elapsed_time: f64,
+    /// The random number generator for the latency of participators
+    pub rng: Option<StdRng>,
     /// The pool to store the bond value from relayer or challenger
     pub submit_bond_pool: f64,
-    /// We do not simulate the redeem action and the fee, so the debt will occure when pay from
-    /// treasury
-    pub treasury_debt: f64,
+    /// The treasury collects fee from redeem actions and pays the reward from treasury,
+    /// the balance will be negative when the fee is not enough
+    pub treasury: Treasury,
+    /// The fee of each extrinsic of submission or challenge
+    pub transaction_cost: TransactionCostConfig,
+    /// The total fee paid to the block producers of Darwinia
+    pub transaction_fee: f64,
+    /// The disputed Ethereum block of the game in playing
+    pub current_game: usize,
+    /// The concurrent games not in playing, keyed by the disputed Ethereum block
+    pub games: BTreeMap<usize, GameStatus>,
+}
+
+/// # Game Status
+/// The status of a game on chain, each concurrent game has its own bond pool and challenge timer,
+/// the status will be swapped into `ChainsStatus` when the game is playing
+#[derive(Default, Debug, Clone)]
+pub struct GameStatus {
+    /// The block height of Darwinia when the current round of the game closed
+    pub darwinia_block_hight: usize,
+    /// The block height of Ethereum when the current round of the game closed
+    pub ethereum_block_hight: usize,
+    /// The next Ethereum block that relayer should submit
+    pub submit_target_ethereum_block: usize,
+    /// The list of submission
+    pub submitions: Vec<(usize, usize)>,
+    /// The last relayed block on Darwinia and Ethereum before the game start
+    pub genesis: (usize, usize),
+    /// The headers submitted in this game
+    pub submit_headers: BTreeMap<usize, Vec<(Header, Vec<String>)>>,
+    /// The pool to store the bond value of this game
+    pub submit_bond_pool: f64,
+    /// The time in seconds elapsed in the game
+    pub elapsed_time: f64,
+    /// The relayers lied in this game, the lie is settled in each game on its own
+    pub lies: HashMap<String, bool>,
 }
 
 impl From<ScenarioConfig> for ChainsStatus {
@@ -70,37 +123,43 @@ impl From<ScenarioConfig> for ChainsStatus {
         };
         let relayer_choice = c.relayers[0].choice.clone();
 
+        let genesis = c.ethereum_genesis();
+        let header_chain = match (&c.canonical_headers, c.header) {
+            (Some(headers), _) => Some(headers.clone().into()),
+            (None, Some(h)) => Some(h.into()),
+            _ => None,
+        };
+
         ChainsStatus {
             darwinia_block_hight: c.Dd.unwrap_or(0),
-            ethereum_block_hight: c.De.unwrap_or(100),
+            ethereum_block_hight: genesis + c.De.unwrap_or(100),
             relayers: c
                 .relayers
                 .clone()
                 .into_iter()
                 .fold(HashMap::new(), |mut map, r| {
                     let s: ParticipatorStatus = r.into();
-                    if let Some(n) = &s.name {
-                        map.insert(n.to_string(), s);
-                    } else {
-                        map.insert(format!(" {}", s.id), s);
-                    }
+                    map.insert(s.id.clone(), s);
                     map
                 }),
             challengers: challengers.into_iter().fold(HashMap::new(), |mut map, r| {
                 let s: ParticipatorStatus =
                     ParticipatorStatus::from_challenger_config(r, &relayer_choice);
-                if let Some(n) = &s.name {
-                    map.insert(n.to_string(), s);
-                } else {
-                    map.insert(format!(" {}", s.id), s);
-                }
+                map.insert(s.id.clone(), s);
                 map
             }),
-            submit_target_ethereum_block: c
-                .get_sample_equation()
-                .unwrap()
-                .calculate(0, c.De.unwrap_or(100)),
+            submit_target_ethereum_block: c.disputed_block(),
+            genesis: (0, genesis),
             block_speed_factor: c.F.unwrap_or(2.0),
+            header_chain,
+            block_producer: c
+                .block_time
+                .clone()
+                .map(|b| BlockProducer::new(b, c.seed.unwrap_or(0))),
+            rng: Some(StdRng::seed_from_u64(c.seed.unwrap_or(0))),
+            current_game: c.disputed_block(),
+            treasury: c.treasury.map(|t| t.into()).unwrap_or_default(),
+            transaction_cost: c.transaction_cost.unwrap_or_default(),
             ..Default::default()
         }
     }
@@ -124,7 +183,7 @@ impl ChainsStatus {
         let last_relayed_block = if submition_times > 0 {
             self.submitions[submition_times - 1]
         } else {
-            (0, 0)
+            self.genesis
         };
         format!(
             "ChainsStatus: Darwinia #{}, Ethereum #{}, Submit at Eth(#{}) Last relay Eth(#{}) at #{}\n",
@@ -151,18 +210,33 @@ impl ChainsStatus {
     }
     pub fn fmt_relayers_bar_chart(&self, normalize_value: f64) -> String {
         let mut output = String::new();
+        let with_fee = self.transaction_fee > 0.0;
         for r in self.relayers.iter() {
-            output.push_str(&r.1.format_to_bar_char(normalize_value, VISUALIZED_MAX_LENGTH));
+            output.push_str(&r.1.format_to_bar_char(
+                normalize_value,
+                VISUALIZED_MAX_LENGTH,
+                with_fee,
+            ));
         }
         for c in self.challengers.iter() {
-            output.push_str(&c.1.format_to_bar_char(normalize_value, VISUALIZED_MAX_LENGTH));
+            output.push_str(&c.1.format_to_bar_char(
+                normalize_value,
+                VISUALIZED_MAX_LENGTH,
+                with_fee,
+            ));
+        }
+        if with_fee {
+            output.push_str("fee: transaction fee, ");
         }
         output.push_str("-: slash, +: reward from slash, *: reward from treasury");
         output
     }
     fn submit_by(&mut self, relayer: String, bond: f64, lie: bool) {
+        let fee = self.cost_of(&self.relayers[&relayer]).submission_fee();
         let r = self.relayers.get_mut(&relayer).unwrap();
         r.submit(bond, lie);
+        r.fee += fee;
+        self.transaction_fee += fee;
         self.submit_bond_pool += bond;
     }
     pub fn submit(
@@ -177,25 +251,178 @@ impl ChainsStatus {
         }
         self.submitions
             .push((self.darwinia_block_hight, self.submit_target_ethereum_block));
-        self.ethereum_block_hight += (wait_blocks as f64 / self.block_speed_factor) as usize;
+        if let Some(producer) = self.block_producer.as_mut() {
+            let (time, ethereum_blocks) = producer.produce(wait_blocks);
+            self.elapsed_time += time;
+            self.ethereum_block_hight += ethereum_blocks;
+        } else {
+            self.ethereum_block_hight += (wait_blocks as f64 / self.block_speed_factor) as usize;
+        }
         self.darwinia_block_hight += wait_blocks;
         self.submit_target_ethereum_block = next_target_ethereum_block;
     }
+    /// Submit the header of the target Ethereum block by the relayer, the evil relayer forges the
+    /// header, return false if the header is not valid or contradicts the headers submitted by
+    /// the relayer before
+    pub fn submit_header(&mut self, relayer: &str, lie: bool) -> bool {
+        let chain = match self.header_chain.as_mut() {
+            Some(c) => c,
+            None => return true,
+        };
+        let number = self.submit_target_ethereum_block;
+        let header = if lie {
+            chain.forge(relayer, number, self.genesis.1)
+        } else {
+            chain.header(number)
+        };
+        if !validate(relayer, &header, &self.submit_headers) {
+            return false;
+        }
+        let submitted = self.submit_headers.entry(number).or_default();
+        match submitted.iter_mut().find(|h| h.0 == header) {
+            Some(h) => {
+                if !h.1.iter().any(|r| r == relayer) {
+                    h.1.push(relayer.to_string());
+                }
+            }
+            None => submitted.push((header, vec![relayer.to_string()])),
+        }
+        true
+    }
+    /// Start the next game after the previous game closed, the balances of participators are
+    /// carried as earnings, and the next game starts from the latest confirmed Ethereum block
+    pub fn next_game(&mut self, sample_eq: &dyn Equation, latest_confirm_ethereum_block: usize) {
+        for p in self.relayers.values_mut().chain(self.challengers.values_mut()) {
+            p.carry_earnings();
+        }
+        self.genesis = (self.darwinia_block_hight, latest_confirm_ethereum_block);
+        self.submitions.clear();
+        self.submit_headers.clear();
+        self.submit_target_ethereum_block =
+            sample_eq.calculate(latest_confirm_ethereum_block, self.ethereum_block_hight);
+    }
+    /// Open a concurrent game from the scenario, return the disputed Ethereum block of the game
+    pub fn open_game(&mut self, c: &ScenarioConfig) -> usize {
+        let disputed_block = c.disputed_block();
+        for r in c.relayers.iter() {
+            self.relayers
+                .entry(r.id().to_string())
+                .or_insert_with(|| r.clone().into());
+        }
+        self.games.insert(
+            disputed_block,
+            GameStatus {
+                darwinia_block_hight: c.Dd.unwrap_or(0),
+                ethereum_block_hight: c.ethereum_genesis() + c.De.unwrap_or(100),
+                submit_target_ethereum_block: disputed_block,
+                genesis: (0, c.ethereum_genesis()),
+                ..Default::default()
+            },
+        );
+        disputed_block
+    }
+    /// Swap the game disputed on the Ethereum block into playing
+    pub fn switch_game(&mut self, disputed_block: usize) -> Result<(), Error> {
+        if disputed_block == self.current_game {
+            return Ok(());
+        }
+        let mut game = self
+            .games
+            .remove(&disputed_block)
+            .ok_or(Error::UnknownError(
+                "the game disputed on the block is not opened",
+            ))?;
+        swap(&mut self.darwinia_block_hight, &mut game.darwinia_block_hight);
+        swap(&mut self.ethereum_block_hight, &mut game.ethereum_block_hight);
+        swap(
+            &mut self.submit_target_ethereum_block,
+            &mut game.submit_target_ethereum_block,
+        );
+        swap(&mut self.submitions, &mut game.submitions);
+        swap(&mut self.genesis, &mut game.genesis);
+        swap(&mut self.submit_headers, &mut game.submit_headers);
+        swap(&mut self.submit_bond_pool, &mut game.submit_bond_pool);
+        swap(&mut self.elapsed_time, &mut game.elapsed_time);
+        for (id, r) in self.relayers.iter_mut() {
+            swap(&mut r.lie, game.lies.entry(id.clone()).or_default());
+        }
+        self.games.insert(self.current_game, game);
+        self.current_game = disputed_block;
+        Ok(())
+    }
+    /// The Darwinia block height of the game disputed on the Ethereum block
+    pub fn game_clock(&self, disputed_block: usize) -> usize {
+        if disputed_block == self.current_game {
+            self.darwinia_block_hight
+        } else {
+            self.games[&disputed_block].darwinia_block_hight
+        }
+    }
+    /// The submission or the challenge from the participator arrives in the challenge window or
+    /// not, the latency is sampled from the latency model of the participator
+    pub fn arrive_in_time(&mut self, participator: &str, challenge_time: usize) -> bool {
+        let challengers = &self.challengers;
+        let p = self
+            .relayers
+            .get(participator)
+            .or_else(|| challengers.get(participator));
+        match (p.and_then(|p| p.latency.as_ref()), self.rng.as_mut()) {
+            (Some(latency), Some(rng)) => latency.sample(rng) <= challenge_time as f64,
+            _ => true,
+        }
+    }
+    /// The transaction cost of the participator, or the one of the chain
+    fn cost_of(&self, p: &ParticipatorStatus) -> TransactionCostConfig {
+        p.transaction_cost.unwrap_or(self.transaction_cost)
+    }
+    /// The game state observed by the participator before deciding the action of the round, a
+    /// participator not in the chain sends no extrinsic, so observes no fee
+    pub fn observe(&self, participator: &str, bond: f64) -> Observation {
+        let fee = match (
+            self.relayers.get(participator),
+            self.challengers.get(participator),
+        ) {
+            (Some(r), _) => self.cost_of(r).submission_fee(),
+            (None, Some(c)) => self.cost_of(c).challenge_fee(),
+            (None, None) => 0.0,
+        };
+        Observation {
+            bond,
+            fee,
+            ..Default::default()
+        }
+    }
     pub fn challenge_by(&mut self, challenger: String, bond: f64) {
+        let fee = self.cost_of(&self.challengers[&challenger]).challenge_fee();
         let challenger = self.challengers.get_mut(&challenger).unwrap();
         challenger.pay += bond;
+        challenger.fee += fee;
+        self.transaction_fee += fee;
         self.submit_bond_pool += bond;
     }
+    /// Settle the slash left in the bond pool of the closed game to treasury
+    pub fn settle_slash(&mut self, slash: f64) {
+        self.submit_bond_pool -= slash;
+        self.treasury.take_unclaimed(slash);
+    }
+    /// Route the portion of slash from the bond pool to treasury, and return the rest of slash
+    pub fn route_slash(&mut self, slash: f64) -> f64 {
+        let rest = self.treasury.take_slash(slash);
+        self.submit_bond_pool -= slash - rest;
+        rest
+    }
 
     pub fn should_balance(&self) {
-        let mut p = self.submit_bond_pool - self.treasury_debt;
+        let mut p = self.submit_bond_pool + self.treasury.slash_income - self.treasury.paid
+            + self.transaction_fee;
+        for (_key, g) in self.games.iter() {
+            p += g.submit_bond_pool;
+        }
         for (_key, r) in self.relayers.iter() {
-            p -= r.pay;
-            p += r.reward();
+            p += r.balance() - r.initial_balance;
         }
         for (_key, c) in self.challengers.iter() {
-            p -= c.pay;
-            p += c.reward();
+            p += c.balance() - c.initial_balance;
         }
 
         // TODO: check the small number is correct and acceptable
@@ -222,7 +449,7 @@ impl ChainsStatus {
                 match reward.from {
                     RewardFrom::Treasure => {
                         r.reward.1 += reward.value;
-                        self.treasury_debt += reward.value;
+                        self.treasury.pay(reward.value);
                     }
                     RewardFrom::Slash => {
                         r.reward.0 += reward.value;
@@ -238,10 +465,12 @@ impl ChainsStatus {
 /// the statue we simulate
 #[derive(Default, Debug, Clone)]
 pub struct ParticipatorStatus {
-    /// id is used when name not provided
-    pub id: usize,
+    /// the stable identifier from the scenario file
+    pub id: String,
     /// name is option field in scenario file
     pub name: Option<String>,
+    /// the initial balance from the scenario file
+    pub initial_balance: f64,
     /// current pay out for bond
     pub pay: f64,
     /// the reward from slash (reward.0) and from treasury (reward.1)
@@ -252,13 +481,25 @@ pub struct ParticipatorStatus {
     pub submit_round: usize,
     /// the relayer has lied or not
     pub lie: bool,
+    /// the transaction fee paid for submissions or challenges
+    pub fee: f64,
+    /// the earnings carried from the previous games
+    pub earnings: f64,
+    /// the latency of submissions in Darwinia blocks
+    pub latency: Option<Distribution>,
+    /// the fee of the extrinsics sent by the participator, instead of the one of the chain
+    pub transaction_cost: Option<TransactionCostConfig>,
 }
 
 impl From<RelayerConfig> for ParticipatorStatus {
     fn from(c: RelayerConfig) -> Self {
         ParticipatorStatus {
-            id: TOTAL_RELAYER.fetch_add(1, Ordering::SeqCst),
+            id: c.id().to_string(),
             name: c.name,
+            initial_balance: c.balance.unwrap_or_default(),
+            // the latency is validated with the scenario
+            latency: c.latency.and_then(|l| l.get_distribution().ok()),
+            transaction_cost: c.transaction_cost,
             ..Default::default()
         }
     }
@@ -266,31 +507,22 @@ impl From<RelayerConfig> for ParticipatorStatus {
 
 impl fmt::Display for ParticipatorStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let balance = self.reward() - self.pay;
-        if let Some(n) = &self.name {
-            if self.submit_round > 0 {
-                // challenger
-                if self.lie {
-                    write!(f, "{}(lie): {} ", n, balance)
-                } else {
-                    write!(f, "{}: {} ", n, balance)
-                }
+        let balance = self.balance();
+        let n = self.name.as_ref().unwrap_or(&self.id);
+        if self.submit_round > 0 {
+            // challenger
+            if self.lie {
+                write!(f, "{}(lie): {} ", n, balance)
             } else {
-                // relayer
-                if self.get_honest_submit_times() > 0 {
-                    write!(f, "{}({}): {} ", n, self.get_honest_submit_times(), balance)
-                } else {
-                    write!(f, "{}: {} ", n, balance)
-                }
+                write!(f, "{}: {} ", n, balance)
             }
         } else {
-            write!(
-                f,
-                "ID {}({}): {} ",
-                self.id,
-                self.get_honest_submit_times(),
-                balance
-            )
+            // relayer
+            if self.get_honest_submit_times() > 0 {
+                write!(f, "{}({}): {} ", n, self.get_honest_submit_times(), balance)
+            } else {
+                write!(f, "{}: {} ", n, balance)
+            }
         }
     }
 }
@@ -310,17 +542,41 @@ impl ParticipatorStatus {
         }
 
         ParticipatorStatus {
-            id: TOTAL_RELAYER.fetch_add(1, Ordering::SeqCst),
+            id: c.id().to_string(),
             name: c.name,
+            initial_balance: c.balance.unwrap_or_default(),
             submit_round: c.choice.len(),
             lie,
+            // the latency is validated with the scenario
+            latency: c.latency.and_then(|l| l.get_distribution().ok()),
+            transaction_cost: c.transaction_cost,
             ..Default::default()
         }
     }
     fn reward(&self) -> f64 {
         self.reward.0 + self.reward.1
     }
-    fn format_to_bar_char(&self, normalize_value: f64, normalize_width: usize) -> String {
+    /// the initial balance, the earnings from the previous games and the balance of current game
+    pub fn balance(&self) -> f64 {
+        self.initial_balance + self.earnings + self.reward() - self.pay - self.fee
+    }
+    fn carry_earnings(&mut self) {
+        self.earnings = self.balance() - self.initial_balance;
+        self.pay = 0.0;
+        self.fee = 0.0;
+        self.reward = (0.0, 0.0);
+        self.submit_times = 0;
+        if self.submit_round == 0 {
+            // the lie of challenger is decided by the scenario, and will be the same in each game
+            self.lie = false;
+        }
+    }
+    fn format_to_bar_char(
+        &self,
+        normalize_value: f64,
+        normalize_width: usize,
+        with_fee: bool,
+    ) -> String {
         let reward_slash_part: usize;
         let reward_treasury_part: usize;
         let slash_part: usize;
@@ -336,9 +592,15 @@ impl ParticipatorStatus {
             reward_treasury_part = 0;
             slash_part = (self.pay / normalize_value * normalize_width as f64) as usize;
         }
+        let fee_column = if with_fee {
+            format!("fee: {:<10.2} ", self.fee)
+        } else {
+            String::new()
+        };
         format!(
-            "● {:<25} {}{}{}\n",
+            "● {:<25} {}{}{}{}\n",
             format!("{}", self),
+            fee_column,
             "-".repeat(slash_part).to_string(),
             "+".repeat(reward_slash_part),
             "*".repeat(reward_treasury_part),
@@ -431,4 +693,53 @@ mod tests {
         assert_eq!(c.relayers["Evil"].reward(), 0.0);
         assert_eq!(c.relayers["Darwinia"].reward(), 30.0);
     }
+    #[test]
+    fn test_transaction_fee() {
+        let mut config = <ScenarioConfig>::from_str(TOML_CONFIG).unwrap();
+        config.transaction_cost = Some(Default::default());
+        config
+            .apply_patch(vec!["transaction_cost.B=1.0", "transaction_cost.H=0.5"])
+            .unwrap();
+        let mut c: ChainsStatus = config.into();
+        c.submit_by("Darwinia".to_string(), 10.0, false);
+        c.submit_by("Darwinia".to_string(), 10.0, false);
+        c.should_balance();
+        assert_eq!(c.relayers["Darwinia"].fee, 3.0);
+        assert_eq!(c.relayers["Darwinia"].balance(), -23.0);
+        assert_eq!(c.transaction_fee, 3.0);
+        assert!(c.fmt_relayers_bar_chart(20.0).contains("fee: 3.00"));
+        // the participator not in the chain observes no fee
+        assert_eq!(c.observe("Nobody", 10.0).fee, 0.0);
+    }
+    #[test]
+    fn test_route_slash_to_treasury() {
+        let mut config = <ScenarioConfig>::from_str(TOML_CONFIG).unwrap();
+        config.treasury = Some(Default::default());
+        config.apply_patch(vec!["treasury.S=0.5"]).unwrap();
+        let mut c: ChainsStatus = config.into();
+        c.submit(
+            vec![("Evil".to_string(), true), ("Darwinia".to_string(), false)],
+            10.0,
+            50,
+            500,
+        );
+        assert_eq!(c.route_slash(10.0), 5.0);
+        c.should_balance();
+        assert_eq!(c.submit_bond_pool, 15.0);
+        assert_eq!(c.treasury.balance, 5.0);
+        c.reward(vec![Reward {
+            from: RewardFrom::Treasure,
+            to: "Darwinia".to_string(),
+            value: 20.0,
+        }]);
+        c.should_balance();
+        assert!(!c.treasury.is_solvent());
+    }
+    #[test]
+    fn test_switch_game() {
+        let config = <ScenarioConfig>::from_str(TOML_CONFIG).unwrap();
+        let mut c: ChainsStatus = config.into();
+        assert!(c.switch_game(c.current_game).is_ok());
+        assert!(c.switch_game(c.current_game + 1).is_err());
+    }
 }
@@ -3,7 +3,7 @@ use std::cmp::min;
 
 use crate::challenge::{ConfigValidate, Equation};
 use crate::error::Error;
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// # Linear waiting function
 /// Here is the linear equation  
@@ -13,7 +13,7 @@ use serde_derive::Deserialize;
 /// e: the parameters affect by the parameters on target network(for example Ethereum)
 ///
 #[allow(non_snake_case)]
-#[derive(Default, Debug, Deserialize, Copy, Clone)]
+#[derive(Default, Debug, Deserialize, Serialize, Copy, Clone)]
 pub struct LinearConfig {
     /// Md: the max value about D portion
     Wd: f64,
@@ -24,31 +24,17 @@ pub struct LinearConfig {
     /// The upper limitation for Darwinia part
     Md: usize,
     /// The upper limitation for target chain part
+    #[allow(dead_code)]
     Me: usize,
 }
 
 impl ConfigValidate for LinearConfig {
     fn validate(&self) -> Result<(), Error> {
         if self.Wd < 0.0 {
-            return Err(Error::ParameterError("Wd should not be negative"));
+            return Err(Error::field("Wd", "should not be negative"));
         }
         if self.We < 0.0 {
-            return Err(Error::ParameterError("We should not be negative"));
-        }
-        Ok(())
-    }
-    fn apply_patch(&mut self, k: &str, v: &str) -> Result<(), Error> {
-        match k {
-            "Wd" => self.Wd = v.parse::<f64>()?,
-            "We" => self.We = v.parse::<f64>()?,
-            "Md" => self.Md = v.parse::<usize>()?,
-            "Me" => self.Me = v.parse::<usize>()?,
-            "C" => self.C = v.parse::<usize>()?,
-            _ => {
-                return Err(Error::PatchParameterError(
-                    "parameter not correct".to_string(),
-                ))
-            }
+            return Err(Error::field("We", "should not be negative"));
         }
         Ok(())
     }
@@ -62,22 +48,3 @@ impl Equation for LinearConfig {
             + self.C
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_apply_patch() {
-        let mut c = LinearConfig::default();
-        c.apply_patch("C", "9").unwrap();
-        c.apply_patch("Wd", "1.234").unwrap();
-        c.apply_patch("We", "4.56").unwrap();
-        c.apply_patch("Md", "8").unwrap();
-        c.apply_patch("Me", "7").unwrap();
-        assert_eq!(c.C, 9);
-        assert_eq!(c.Wd, 1.234);
-        assert_eq!(c.We, 4.56);
-        assert_eq!(c.Md, 8);
-        assert_eq!(c.Me, 7);
-    }
-}
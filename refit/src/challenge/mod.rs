@@ -9,16 +9,31 @@ use crate::error::Error;
 pub mod linear;
 
 /// This trait help the main function calculate the bond from the equation
-pub trait Equation {
+pub trait Equation: EquationClone {
     fn calculate(&self, darwinia_distance: usize, ethereum_distance: usize) -> usize;
 }
 
+/// Clone the boxed equation, the equation should derive `Clone`
+pub trait EquationClone {
+    fn clone_box(&self) -> Box<dyn Equation>;
+}
+
+impl<T: 'static + Equation + Clone> EquationClone for T {
+    fn clone_box(&self) -> Box<dyn Equation> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Equation> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
 /// This trait help the main function
 /// - validating the parameters when loading yaml
-/// - apply patch when user pass it as option `p` from command line
 pub trait ConfigValidate {
     fn validate(&self) -> Result<(), Error>;
-    fn apply_patch(&mut self, k: &str, v: &str) -> Result<(), Error>;
 }
 
 impl Equation for usize {
@@ -0,0 +1,351 @@
+//! Check module
+//! Check the scenario file and report all the problems found, instead of the first one, with the
+//! field path, for example `relayers[1].choice`, and the line and column in the scenario file.
+//! The keys not in the scenario schema are reported, because they are ignored in simulation.
+//!
+//! The line and column of a problem found after parsing is located by searching the field path in
+//! the scenario file, so a field from the scenario it `extends` or `includes` has no location.
+use std::fmt;
+use std::path::Path;
+
+use crate::error::Error;
+use crate::patch::{ignored_path, join_key};
+use crate::scenario::{load_value, Format, ScenarioConfig};
+
+/// # Problem
+/// A problem of the scenario at the field path
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// The field path, for example `challenge_linear.Wd` or `relayers[1].choice`
+    pub path: String,
+    /// The line and column in the scenario file, start from 1
+    pub location: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((line, column)) = self.location {
+            write!(f, "{}:{}: ", line, column)?;
+        }
+        if !self.path.is_empty() {
+            write!(f, "{}: ", self.path)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Check the scenario file, and return the problems found
+pub fn check_file(file_name: &str) -> Result<Vec<Problem>, Error> {
+    let contents = std::fs::read_to_string(file_name)?;
+    let format = Format::from_file(file_name, &contents);
+    if let Some(p) = parse_problem(&contents, format) {
+        return Ok(vec![p]);
+    }
+    let value = match load_value(Path::new(file_name), &mut Vec::new()) {
+        Ok(v) => v,
+        Err(e) => return Ok(vec![problem(&contents, "", message(&e))]),
+    };
+
+    let mut unknown_keys = Vec::new();
+    let mut callback = |p: serde_ignored::Path| unknown_keys.push(ignored_path(&p));
+    let config: Result<ScenarioConfig, _> =
+        serde_path_to_error::deserialize(serde_ignored::Deserializer::new(value, &mut callback));
+    let mut problems: Vec<Problem> = unknown_keys
+        .iter()
+        .map(|k| problem(&contents, k, "unknown key, it is ignored".to_string()))
+        .collect();
+    match config {
+        Ok(c) => {
+            let dir = Path::new(file_name)
+                .parent()
+                .unwrap_or_else(|| Path::new("."));
+            for (path, msg) in check_config(c, dir) {
+                problems.push(problem(&contents, &path, msg));
+            }
+        }
+        Err(e) => {
+            problems.push(problem(
+                &contents,
+                &error_path(e.path()),
+                format!("{}", e.inner()),
+            ));
+        }
+    }
+    if problems.is_empty() {
+        if let Err(e) = ScenarioConfig::from_file(file_name) {
+            let (path, message) = field_problem("", e);
+            problems.push(problem(&contents, &path, message));
+        }
+    }
+    Ok(problems)
+}
+
+fn problem(contents: &str, path: &str, message: String) -> Problem {
+    Problem {
+        path: path.to_string(),
+        location: locate(contents, path),
+        message,
+    }
+}
+
+fn message(e: &Error) -> String {
+    match e {
+        Error::ParameterError(s) => s.to_string(),
+        _ => format!("{}", e),
+    }
+}
+
+/// The problem of the file can not be parsed, with the location from the parser
+fn parse_problem(contents: &str, format: Format) -> Option<Problem> {
+    let (location, message) = match format {
+        Format::Toml => {
+            let e = toml::from_str::<toml::Value>(contents).err()?;
+            (e.line_col().map(|(l, c)| (l + 1, c + 1)), format!("{}", e))
+        }
+        Format::Yaml => {
+            let e = serde_yaml::from_str::<serde_json::Value>(contents).err()?;
+            (
+                e.location().map(|l| (l.line(), l.column())),
+                format!("{}", e),
+            )
+        }
+        Format::Json => {
+            let e = serde_json::from_str::<serde_json::Value>(contents).err()?;
+            (Some((e.line(), e.column())), format!("{}", e))
+        }
+    };
+    Some(Problem {
+        path: String::new(),
+        location,
+        message: format!("can not be parsed as {}: {}", format.name(), message),
+    })
+}
+
+fn error_path(path: &serde_path_to_error::Path) -> String {
+    path.iter().fold(String::new(), |p, s| match s {
+        serde_path_to_error::Segment::Seq { index } => format!("{}[{}]", p, index),
+        serde_path_to_error::Segment::Map { key } => join_key(p, key),
+        serde_path_to_error::Segment::Enum { variant } => join_key(p, variant),
+        serde_path_to_error::Segment::Unknown => join_key(p, "?"),
+    })
+}
+
+/// Check the parameters of the scenario with the rules of validation, and load the Ethereum
+/// headers, return the field paths and the messages of problems
+fn check_config(c: ScenarioConfig, dir: &Path) -> Vec<(String, String)> {
+    let mut problems: Vec<(String, String)> = c
+        .problems()
+        .into_iter()
+        .map(|e| field_problem("", e))
+        .collect();
+    if let Err(e) = c.clone().load_ethereum_headers(dir) {
+        problems.push(match e {
+            Error::FieldError(path, message) => (path, message),
+            e => ("ethereum_headers".to_string(), message(&e)),
+        });
+    }
+    problems
+}
+
+/// The field path and the message of the problem found in the table at the path
+fn field_problem(path: &str, e: Error) -> (String, String) {
+    match e.in_table(path) {
+        Error::FieldError(path, message) => (path, message),
+        e => (path.to_string(), message(&e)),
+    }
+}
+
+/// Locate the field path in the scenario file, the keys are searched in order, and the index of
+/// list is counted by the `[[table]]` headers of TOML or the items of YAML and JSON lists
+fn locate(contents: &str, path: &str) -> Option<(usize, usize)> {
+    if path.is_empty() {
+        return None;
+    }
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut cursor: Option<(usize, usize)> = None;
+    let mut start = 0;
+    for segment in path.split('.') {
+        let (key, indexes) = match segment.find('[') {
+            Some(i) => (&segment[..i], &segment[i..]),
+            None => (segment, ""),
+        };
+        let (l, c) =
+            (start..lines.len()).find_map(|l| key_column(lines[l], key).map(|c| (l, c)))?;
+        cursor = Some((l, c));
+        start = l + 1;
+        for index in indexes
+            .split(&['[', ']'][..])
+            .filter_map(|i| i.parse::<usize>().ok())
+        {
+            let (l, _) = cursor.unwrap();
+            if let Some((l, c)) = locate_item(&lines, l, index) {
+                cursor = Some((l, c));
+                start = l;
+            }
+        }
+    }
+    cursor.map(|(l, c)| (l + 1, c + 1))
+}
+
+/// The column of the key if the line defines the key
+fn key_column(line: &str, key: &str) -> Option<usize> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with('#') {
+        return None;
+    }
+    let indent = line.len() - trimmed.len();
+    let header = trimmed.trim_start_matches('[');
+    if header.len() < trimmed.len() {
+        let name = header.split(']').next()?;
+        let last = name.rsplit('.').next()?;
+        return if last.trim() == key {
+            Some(indent + (trimmed.len() - header.len()) + name.len() - last.len())
+        } else {
+            None
+        };
+    }
+    let item = trimmed.trim_start_matches(&['-', '{', ' '][..]);
+    let quoted = item.trim_start_matches('"');
+    let rest = quoted.strip_prefix(key)?;
+    let rest = rest.strip_prefix('"').unwrap_or(rest).trim_start();
+    if rest.starts_with('=') || rest.starts_with(':') {
+        Some(indent + trimmed.len() - quoted.len())
+    } else {
+        None
+    }
+}
+
+/// The location of the item of the list defined at the line
+fn locate_item(lines: &[&str], line: usize, index: usize) -> Option<(usize, usize)> {
+    let header = lines[line].trim();
+    if header.starts_with("[[") {
+        let l = (line..lines.len())
+            .filter(|l| lines[*l].trim() == header)
+            .nth(index)?;
+        return Some((l, lines[l].len() - lines[l].trim_start().len()));
+    }
+    let first = (line + 1..lines.len()).find(|l| {
+        let t = lines[*l].trim_start();
+        t.starts_with('-') || t.starts_with('{')
+    })?;
+    let indent = lines[first].len() - lines[first].trim_start().len();
+    let l = (first..lines.len())
+        .filter(|l| {
+            let t = lines[*l].trim_start();
+            lines[*l].len() - t.len() == indent && (t.starts_with('-') || t.starts_with('{'))
+        })
+        .nth(index)?;
+    Some((l, indent))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    #[test]
+    fn test_check_file() {
+        let dir = std::env::temp_dir().join(format!("refit-check-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("bad.toml");
+        fs::write(
+            &file,
+            r#"challenge_function = "linear"
+target_function = "half"
+bond_function = "10.0"
+reward_function = "split"
+
+[challenge_linear]
+Wd = -1.0
+We = 0.0
+C  = 1
+Md = 100
+Me = 100
+B = 1
+
+[reward_split]
+P = 0.5
+
+[[relayers]]
+name = "Evil"
+choice = "LL"
+
+[[relayers]]
+name = "Honest"
+choice = "HXH"
+"#,
+        )
+        .unwrap();
+        let problems = check_file(file.to_str().unwrap()).unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|p| format!("{}", p))
+                .collect::<Vec<_>>(),
+            vec![
+                "12:1: challenge_linear.B: unknown key, it is ignored",
+                "7:1: challenge_linear.Wd: should not be negative",
+                "23:1: relayers[1].choice: 'X' at position 2 should be 'H', 'L', 'N'",
+            ]
+        );
+
+        fs::write(&file, "title = \n").unwrap();
+        let problems = check_file(file.to_str().unwrap()).unwrap();
+        assert_eq!(problems[0].location, Some((1, 9)));
+
+        let file = dir.join("bad.yml");
+        fs::write(
+            &file,
+            "challenge_function: \"10\"\ntarget_function: half\nbond_function: \"10.0\"\nreward_function: split\nreward_split:\n  P: 0.5\nrelayers:\n  - name: Evil\n    choice: LL\n  - choice: 3\n",
+        )
+        .unwrap();
+        let problems = check_file(file.to_str().unwrap()).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].path, "relayers[1].choice");
+        assert_eq!(problems[0].location, Some((10, 5)));
+
+        // the same rules and messages as the validation in simulation
+        let file = dir.join("roles.toml");
+        fs::write(
+            &file,
+            "challenge_function = \"10\"\ntarget_function = \"half\"\nbond_function = \"10.0\"\nreward_function = \"split\"\n\n[reward_split]\nP = 0.5\n\n[[relayers]]\nchoice = \"H\"\n\n[[relayers]]\nchoice = \"H\"\n\n[[participants]]\nrole = \"challenger\"\nchoice = \"1\"\n\n[[participants]]\nchoice = \"1\"\n",
+        )
+        .unwrap();
+        let problems = check_file(file.to_str().unwrap()).unwrap();
+        assert_eq!(
+            problems
+                .iter()
+                .map(|p| format!("{}", p))
+                .collect::<Vec<_>>(),
+            vec![
+                "participants[1].role: the role should be relayer or challenger",
+                "9:3: relayers: there should be only one relayer in relayer-challenger mode",
+            ]
+        );
+        match ScenarioConfig::from_file(file.to_str().unwrap()) {
+            Err(Error::FieldError(path, message)) => {
+                assert_eq!(
+                    (path.as_str(), message.as_str()),
+                    (problems[0].path.as_str(), problems[0].message.as_str())
+                )
+            }
+            _ => panic!("the scenario should be invalid"),
+        }
+        fs::remove_dir_all(dir).unwrap();
+    }
+    #[test]
+    fn test_check_scenarios() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../scenario");
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_file() {
+                assert_eq!(
+                    check_file(path.to_str().unwrap()).unwrap(),
+                    vec![],
+                    "{:?}",
+                    path
+                );
+            }
+        }
+    }
+}
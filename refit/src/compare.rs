@@ -0,0 +1,259 @@
+//! Compare module
+//! Play two scenarios, or one scenario with two patch sets, and show the differences of each
+//! round in bond, challenge time and target block, the final balance of each participant, and the
+//! summary of the games.  The differences are `b - a`, and can be serialized as JSON.
+use std::collections::BTreeSet;
+use std::fmt;
+
+use serde_derive::Serialize;
+
+use crate::chain::ChainsStatus;
+use crate::error::Error;
+use crate::game::{run, Round};
+use crate::scenario::ScenarioConfig;
+
+/// # Diff
+/// The values of the two games and the difference, the value absent in a game is `None`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Diff {
+    pub a: Option<f64>,
+    pub b: Option<f64>,
+    pub delta: Option<f64>,
+}
+
+impl Diff {
+    fn new(a: Option<f64>, b: Option<f64>) -> Self {
+        Diff {
+            a,
+            b,
+            delta: a.and_then(|a| b.map(|b| b - a)),
+        }
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = |v: Option<f64>| v.map_or("-".to_string(), |v| v.to_string());
+        write!(f, "{} -> {}", value(self.a), value(self.b))?;
+        match self.delta {
+            Some(d) if d > 0.0 => write!(f, " (+{})", d),
+            Some(d) if d < 0.0 => write!(f, " ({})", d),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// # Round Diff
+/// The differences of a submit round
+#[derive(Debug, Serialize)]
+pub struct RoundDiff {
+    /// The submit round, start from 1
+    pub round: usize,
+    pub bond: Diff,
+    pub challenge_time: Diff,
+    pub target_ethereum_block: Diff,
+}
+
+/// # Balance Diff
+/// The differences of the final balance of a participant
+#[derive(Debug, Serialize)]
+pub struct BalanceDiff {
+    pub id: String,
+    pub balance: Diff,
+}
+
+/// # Comparison
+/// The differences of the two games
+#[derive(Debug, Serialize)]
+pub struct Comparison {
+    /// The label of the game a
+    pub a: String,
+    /// The label of the game b
+    pub b: String,
+    pub rounds: Vec<RoundDiff>,
+    pub balances: Vec<BalanceDiff>,
+    /// The differences of the duration, rounds, max bond value, elapsed time and transaction fee
+    pub summary: Vec<(String, Diff)>,
+}
+
+impl Comparison {
+    /// Serialize the comparison as JSON
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self)
+            .map_err(|_| Error::UnknownError("the comparison can not be serialized"))
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "A: {}\nB: {}", self.a, self.b)?;
+        writeln!(f, "Rounds:")?;
+        for r in self.rounds.iter() {
+            writeln!(
+                f,
+                "\t#{} Bond: {}, Challenge Time: {}, Target Block: {}",
+                r.round, r.bond, r.challenge_time, r.target_ethereum_block
+            )?;
+        }
+        writeln!(f, "Balances:")?;
+        for b in self.balances.iter() {
+            writeln!(f, "\t{}: {}", b.id, b.balance)?;
+        }
+        write!(f, "Summary:")?;
+        for (name, d) in self.summary.iter() {
+            write!(f, "\n\t{}: {}", name, d)?;
+        }
+        Ok(())
+    }
+}
+
+/// Play the two scenarios, and compare the games
+pub fn compare(
+    a: (&str, &ScenarioConfig),
+    b: (&str, &ScenarioConfig),
+) -> Result<Comparison, Error> {
+    if a.1.games.is_some() || b.1.games.is_some() {
+        return Err(Error::ParameterError(
+            "Concurrent games can not be compared",
+        ));
+    }
+    let (status_a, result_a) = run(a.1)?;
+    let (status_b, result_b) = run(b.1)?;
+
+    let round = |rounds: &[Round], i: usize, value: fn(&Round) -> f64| rounds.get(i).map(value);
+    let rounds = (0..result_a.rounds.len().max(result_b.rounds.len()))
+        .map(|i| {
+            let diff = |value: fn(&Round) -> f64| {
+                Diff::new(
+                    round(&result_a.rounds, i, value),
+                    round(&result_b.rounds, i, value),
+                )
+            };
+            RoundDiff {
+                round: i + 1,
+                bond: diff(|r| r.bond),
+                challenge_time: diff(|r| r.challenge_time as f64),
+                target_ethereum_block: diff(|r| r.target_ethereum_block as f64),
+            }
+        })
+        .collect();
+
+    let balance = |status: &ChainsStatus, id: &str| {
+        status
+            .relayers
+            .get(id)
+            .or_else(|| status.challengers.get(id))
+            .map(|s| s.balance())
+    };
+    let ids: BTreeSet<&String> = status_a
+        .relayers
+        .keys()
+        .chain(status_a.challengers.keys())
+        .chain(status_b.relayers.keys())
+        .chain(status_b.challengers.keys())
+        .collect();
+    let balances = ids
+        .into_iter()
+        .map(|id| BalanceDiff {
+            id: id.clone(),
+            balance: Diff::new(balance(&status_a, id), balance(&status_b, id)),
+        })
+        .collect();
+
+    let summary = vec![
+        (
+            "Duration".to_string(),
+            Diff::new(
+                Some(result_a.duration as f64),
+                Some(result_b.duration as f64),
+            ),
+        ),
+        (
+            "Rounds".to_string(),
+            Diff::new(
+                Some(result_a.rounds.len() as f64),
+                Some(result_b.rounds.len() as f64),
+            ),
+        ),
+        (
+            "Max Bond Value".to_string(),
+            Diff::new(Some(result_a.max_bond_value), Some(result_b.max_bond_value)),
+        ),
+        (
+            "Elapsed Time".to_string(),
+            Diff::new(Some(result_a.elapsed_time), Some(result_b.elapsed_time)),
+        ),
+        (
+            "Transaction Fee".to_string(),
+            Diff::new(
+                Some(status_a.transaction_fee),
+                Some(status_b.transaction_fee),
+            ),
+        ),
+    ];
+    Ok(Comparison {
+        a: a.0.to_string(),
+        b: b.0.to_string(),
+        rounds,
+        balances,
+        summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn test_compare() {
+        let scenario = r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "linear"
+			reward_function = "split"
+
+			[bond_linear]
+			W = 1.0
+			C = 10.0
+			M = 100.0
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LL"
+
+			[[relayers]]
+			name = "Honest"
+			choice = "HHH"
+			"#;
+        let a = <ScenarioConfig>::from_str(scenario).unwrap();
+        let mut b = a.clone();
+        b.apply_patch(vec![
+            "bond_linear.C=20",
+            "relayers[0].choice=L",
+            "relayers[1].choice=H",
+        ])
+        .unwrap();
+        let c = compare(("a", &a), ("b", &b)).unwrap();
+
+        assert_eq!(c.rounds.len(), 4);
+        assert_eq!(c.rounds[0].bond.delta, Some(10.0));
+        assert_eq!(c.rounds[0].challenge_time.delta, Some(0.0));
+        // the game b closes two rounds earlier
+        assert_eq!(c.rounds[2].bond.b, None);
+        assert_eq!(c.rounds[2].bond.delta, None);
+        assert_eq!(
+            c.balances.iter().map(|b| b.id.as_str()).collect::<Vec<_>>(),
+            vec!["Darwinia", "Evil", "Honest"]
+        );
+        assert_eq!(c.balances[1].balance, Diff::new(Some(-23.0), Some(-21.0)));
+        assert_eq!(c.summary[1].1, Diff::new(Some(4.0), Some(2.0)));
+        assert_eq!(c.to_string().lines().count(), 2 + 1 + 4 + 1 + 3 + 1 + 5);
+
+        let json: serde_json::Value = serde_json::from_str(&c.to_json().unwrap()).unwrap();
+        assert_eq!(json["rounds"][0]["bond"]["delta"], 10.0);
+        assert_eq!(json["summary"][1][0], "Rounds");
+    }
+}
@@ -0,0 +1,89 @@
+//! Transaction Cost module
+//! Submitting an Ethereum header or a challenge on Darwinia is an extrinsic, which costs weight
+//! and fee.  The fee is paid to the block producers of Darwinia, so it is out of the bond pool and
+//! the treasury.
+//!
+//! submission fee = (B + H * N) * Ms
+//! challenge fee = B * Mc
+use serde_derive::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// # Transaction Cost Config
+/// The fee of each extrinsic is a base fee with the fee of headers in the extrinsic, and a
+/// multiplier for the kind of extrinsic
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Copy, Clone)]
+pub struct TransactionCostConfig {
+    /// B: the base fee of each extrinsic
+    B: f64,
+    /// H: the fee for each header in the extrinsic
+    #[serde(default)]
+    H: f64,
+    /// N: the headers in each submission, for example, proposal-only mode submits many headers
+    #[serde(default = "default_headers")]
+    N: usize,
+    /// Ms: the fee multiplier of submissions
+    #[serde(default = "default_multiplier")]
+    Ms: f64,
+    /// Mc: the fee multiplier of challenges
+    #[serde(default = "default_multiplier")]
+    Mc: f64,
+}
+
+fn default_headers() -> usize {
+    1
+}
+
+fn default_multiplier() -> f64 {
+    1.0
+}
+
+impl Default for TransactionCostConfig {
+    fn default() -> Self {
+        TransactionCostConfig {
+            B: 0.0,
+            H: 0.0,
+            N: default_headers(),
+            Ms: default_multiplier(),
+            Mc: default_multiplier(),
+        }
+    }
+}
+
+impl TransactionCostConfig {
+    pub fn validate(&self) -> Result<(), Error> {
+        for (key, v) in [("B", self.B), ("H", self.H), ("Ms", self.Ms), ("Mc", self.Mc)].iter() {
+            if *v < 0.0 {
+                return Err(Error::field(key, "should not be negative"));
+            }
+        }
+        Ok(())
+    }
+    /// The fee of a header submission
+    pub fn submission_fee(&self) -> f64 {
+        (self.B + self.H * self.N as f64) * self.Ms
+    }
+    /// The fee of a challenge
+    pub fn challenge_fee(&self) -> f64 {
+        self.B * self.Mc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_fee() {
+        let mut c = TransactionCostConfig::default();
+        assert_eq!(c.submission_fee(), 0.0);
+        c.B = 1.0;
+        c.H = 0.5;
+        c.N = 4;
+        c.Mc = 2.0;
+        assert_eq!(c.submission_fee(), 3.0);
+        assert_eq!(c.challenge_fee(), 2.0);
+        c.Ms = -1.0;
+        assert!(c.validate().is_err());
+    }
+}
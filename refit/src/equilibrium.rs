@@ -0,0 +1,288 @@
+//! Equilibrium module
+//! Simulate the payoff of each strategy profile of the participants, and find the pure-strategy
+//! Nash equilibria, in which no participant earns more by changing the choice alone.
+//!
+//! The strategy space of a relayer is the choices made of `H`, `L` and `N` of the length, the
+//! shorter choices are covered by `N`, and the strategy space of a challenger is the bit strings
+//! of `0` and `1` up to the length.  The payoff is the balance earned in the game, and the
+//! Darwinia relayer is not a player.
+use std::fmt;
+
+use crate::error::Error;
+use crate::game::run;
+use crate::scenario::ScenarioConfig;
+
+/// The max strategy profiles to simulate
+static MAX_PROFILES: usize = 100_000;
+
+/// The payoffs less than this are deemed as the same
+static EPSILON: f64 = 0.000_000_01;
+
+/// # Player
+/// The participant and the strategy space
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub id: String,
+    pub strategies: Vec<String>,
+    /// The index of the honest strategy
+    pub honest: usize,
+}
+
+/// # Equilibria
+/// The payoff matrix of the strategy profiles and the pure-strategy Nash equilibria
+#[derive(Debug)]
+pub struct Equilibria {
+    pub players: Vec<Player>,
+    /// The payoffs of each player for each profile, the profile is indexed in mixed radix of the
+    /// strategy spaces with the first player as the lowest digit
+    pub payoffs: Vec<Vec<f64>>,
+    /// The profiles of the pure-strategy Nash equilibria
+    pub equilibria: Vec<Vec<usize>>,
+}
+
+impl Equilibria {
+    fn index(&self, profile: &[usize]) -> usize {
+        profile
+            .iter()
+            .zip(self.players.iter())
+            .rev()
+            .fold(0, |i, (s, p)| i * p.strategies.len() + s)
+    }
+    fn profile(&self, mut index: usize) -> Vec<usize> {
+        self.players
+            .iter()
+            .map(|p| {
+                let s = index % p.strategies.len();
+                index /= p.strategies.len();
+                s
+            })
+            .collect()
+    }
+    /// The payoffs of the players for the profile
+    pub fn payoff(&self, profile: &[usize]) -> &[f64] {
+        &self.payoffs[self.index(profile)]
+    }
+    /// The profile all the players are honest
+    pub fn honest(&self) -> Vec<usize> {
+        self.players.iter().map(|p| p.honest).collect()
+    }
+    /// The strategy of the player earning most when the others play as the profile
+    pub fn best_response(&self, player: usize, profile: &[usize]) -> usize {
+        let mut profile = profile.to_vec();
+        let mut best = (profile[player], self.payoff(&profile)[player]);
+        for s in 0..self.players[player].strategies.len() {
+            profile[player] = s;
+            let payoff = self.payoff(&profile)[player];
+            if payoff > best.1 + EPSILON {
+                best = (s, payoff);
+            }
+        }
+        best.0
+    }
+    /// No player earns more by changing the strategy alone
+    pub fn is_equilibrium(&self, profile: &[usize]) -> bool {
+        (0..self.players.len()).all(|i| self.best_response(i, profile) == profile[i])
+    }
+    fn fmt_profile(&self, profile: &[usize]) -> String {
+        let payoff = self.payoff(profile);
+        self.players
+            .iter()
+            .enumerate()
+            .map(|(i, p)| format!("{}: {} ({})", p.id, p.strategies[profile[i]], payoff[i]))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+    /// The payoff matrix, each line is a profile with the payoffs
+    pub fn fmt_matrix(&self) -> String {
+        (0..self.payoffs.len())
+            .map(|i| format!("{}\n", self.fmt_profile(&self.profile(i))))
+            .collect()
+    }
+}
+
+impl fmt::Display for Equilibria {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Players: {}, Profiles: {}",
+            self.players
+                .iter()
+                .map(|p| format!("{}({} strategies)", p.id, p.strategies.len()))
+                .collect::<Vec<String>>()
+                .join(", "),
+            self.payoffs.len()
+        )?;
+        let honest = self.honest();
+        writeln!(f, "Honest Profile: {}", self.fmt_profile(&honest))?;
+        writeln!(
+            f,
+            "Honest Profile is a Nash Equilibrium: {}",
+            self.is_equilibrium(&honest)
+        )?;
+        for (i, p) in self.players.iter().enumerate() {
+            let mut profile = honest.clone();
+            profile[i] = self.best_response(i, &honest);
+            writeln!(
+                f,
+                "Best Response of {} to the Honest: {} ({})",
+                p.id,
+                p.strategies[profile[i]],
+                self.payoff(&profile)[i]
+            )?;
+        }
+        write!(f, "Pure Nash Equilibria: {}", self.equilibria.len())?;
+        for e in self.equilibria.iter() {
+            write!(f, "\n\t{}", self.fmt_profile(e))?;
+        }
+        Ok(())
+    }
+}
+
+/// All the strings of the alphabet in the lengths
+fn strings(alphabet: &str, lengths: std::ops::RangeInclusive<usize>) -> Vec<String> {
+    let mut all = Vec::new();
+    let mut strings = vec![String::new()];
+    for l in 1..=*lengths.end() {
+        strings = strings
+            .iter()
+            .flat_map(|s| alphabet.chars().map(move |a| format!("{}{}", s, a)))
+            .collect();
+        if lengths.contains(&l) {
+            all.extend(strings.iter().cloned());
+        }
+    }
+    all
+}
+
+/// Simulate the payoffs of the players in all the strategy profiles of the choices in the length
+pub fn solve(config: &ScenarioConfig, length: usize) -> Result<Equilibria, Error> {
+    if config.games.is_some() {
+        return Err(Error::ParameterError("Concurrent games can not be solved"));
+    }
+    if length == 0 {
+        return Err(Error::ParameterError(
+            "the length of choices should be positive",
+        ));
+    }
+    let relayers = config.relayers.len() - config.challengers.is_none() as usize;
+    let challengers = config
+        .challengers
+        .as_ref()
+        .map(Vec::len)
+        .unwrap_or_default();
+    // the relayer chooses in 3 ^ length strategies and the challenger in 2 ^ (length + 1) - 2
+    let mut total = Some(1usize);
+    for _ in 0..relayers {
+        total = total.and_then(|t| t.checked_mul(3usize.checked_pow(length as u32)?));
+    }
+    for _ in 0..challengers {
+        total = total.and_then(|t| t.checked_mul(2usize.checked_pow(length as u32 + 1)? - 2));
+    }
+    let total = total
+        .filter(|t| *t <= MAX_PROFILES)
+        .ok_or(Error::ParameterError(
+            "too many strategy profiles, please reduce the length",
+        ))?;
+
+    let relayers = strings("HLN", length..=length);
+    let honest_relayer = "H".repeat(length);
+    let mut players: Vec<Player> = config
+        .relayers
+        .iter()
+        .filter(|r| r.id() != "Darwinia")
+        .map(|r| Player {
+            id: r.id().to_string(),
+            strategies: relayers.clone(),
+            honest: relayers.iter().position(|s| *s == honest_relayer).unwrap(),
+        })
+        .collect();
+    let challengers = strings("01", 1..=length);
+    let honest_challenger = "1".repeat(length);
+    for c in config.challengers.iter().flatten() {
+        players.push(Player {
+            id: c.id().to_string(),
+            strategies: challengers.clone(),
+            honest: challengers
+                .iter()
+                .position(|s| *s == honest_challenger)
+                .unwrap(),
+        });
+    }
+    if players.is_empty() {
+        return Err(Error::ParameterError("there is no player in the scenario"));
+    }
+
+    let mut e = Equilibria {
+        players,
+        payoffs: Vec::with_capacity(total),
+        equilibria: Vec::new(),
+    };
+    for i in 0..total {
+        let profile = e.profile(i);
+        let mut c = config.clone();
+        for (p, s) in e.players.iter().zip(profile.iter()) {
+            c.set_choice(&p.id, &p.strategies[*s])?;
+        }
+        let (chains_status, _) = run(&c)?;
+        e.payoffs.push(
+            e.players
+                .iter()
+                .map(|p| {
+                    let s = chains_status
+                        .relayers
+                        .get(&p.id)
+                        .or_else(|| chains_status.challengers.get(&p.id))
+                        .unwrap();
+                    s.balance() - s.initial_balance
+                })
+                .collect(),
+        );
+    }
+    e.equilibria = (0..total)
+        .map(|i| e.profile(i))
+        .filter(|p| e.is_equilibrium(p))
+        .collect();
+    Ok(e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn test_solve() {
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10.0"
+			reward_function = "split"
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "L"
+
+			[[relayers]]
+			name = "Honest"
+			choice = "H"
+			"#,
+        )
+        .unwrap();
+        let e = solve(&config, 1).unwrap();
+        assert_eq!(e.players.len(), 2);
+        assert_eq!(e.payoffs.len(), 9);
+        let honest = e.honest();
+        assert_eq!(honest, vec![0, 0]);
+        assert!(e.is_equilibrium(&honest));
+        assert_eq!(e.payoff(&[1, 0]), &[-10.0, 5.0]);
+        assert_eq!(e.best_response(0, &honest), 0);
+        assert!(e.equilibria.contains(&honest));
+        assert!(!e.equilibria.contains(&vec![1, 0]));
+        assert_eq!(e.fmt_matrix().lines().count(), 9);
+
+        assert!(solve(&config, 20).is_err());
+    }
+}
@@ -2,16 +2,24 @@
 use failure_derive::*;
 use toml;
 
+use crate::patch::join_key;
+
 #[derive(Fail, Debug)]
 pub enum Error {
-    #[fail(display = "The config should be TOML: {}", 0)]
-    TOMLParseError(toml::de::Error),
+    #[fail(display = "The scenario can not be parsed as {}: {}", 0, 1)]
+    ScenarioParseError(&'static str, String),
+    #[fail(display = "The scenario can not be extended or included: {}", 0)]
+    ScenarioIncludeError(String),
     #[fail(display = "Unexpected parameter: {}", 0)]
     ParameterError(&'static str),
+    #[fail(display = "Unexpected parameter `{}`: {}", 0, 1)]
+    FieldError(String, String),
     #[fail(display = "Command line args or options are not correct: {}", 0)]
     CliError(String),
     #[fail(display = "Patch Parameter `{}` is not valid", 0)]
     PatchParameterError(String),
+    #[fail(display = "The Ethereum headers are not correct: {}", 0)]
+    HeaderFixtureError(String),
     #[cfg(feature = "plot")]
     #[fail(display = "Plotting Error: {}", 0)]
     PlotError(String),
@@ -19,6 +27,21 @@ pub enum Error {
     UnknownError(&'static str),
 }
 
+impl Error {
+    /// The error of the field at the path, for example `relayers[1].choice`
+    pub fn field(path: &str, message: &str) -> Self {
+        Error::FieldError(path.to_string(), message.to_string())
+    }
+    /// The error in the table at the path, the field path is prefixed with it
+    pub fn in_table(self, path: &str) -> Self {
+        match self {
+            Error::FieldError(k, m) => Error::FieldError(join_key(path.to_string(), &k), m),
+            Error::ParameterError(m) => Error::field(path, m),
+            e => e,
+        }
+    }
+}
+
 impl From<std::num::ParseFloatError> for Error {
     fn from(_: std::num::ParseFloatError) -> Self {
         Error::ParameterError("Parameter cannot parse as number")
@@ -39,7 +62,13 @@ impl From<std::io::Error> for Error {
 
 impl From<toml::de::Error> for Error {
     fn from(err: toml::de::Error) -> Self {
-        Error::TOMLParseError(err)
+        Error::ScenarioParseError("TOML", format!("{}", err))
+    }
+}
+
+impl From<serde_yaml::Error> for Error {
+    fn from(err: serde_yaml::Error) -> Self {
+        Error::ScenarioParseError("YAML", format!("{}", err))
     }
 }
 
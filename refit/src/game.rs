@@ -0,0 +1,872 @@
+//! Game module
+//!
+//! A game starts from a submission on the target Ethereum block, and keeps sampling until there is
+//! no more choice in the scenario.  The `play` function plays one game on the `ChainsStatus`, so
+//! the games can be played one after another over a long chain history.
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use colored::Colorize;
+
+use crate::bond::Equation as BondEq;
+use crate::chain::{ChainsStatus, Reward, RewardFrom};
+use crate::challenge::Equation as ChallengeEq;
+use crate::error::Error;
+use crate::reward::Equation as RewardEq;
+use crate::sample::Equation as TargetEq;
+use crate::scenario::{RelayPositions, Role, ScenarioConfig, ScenarioConfigIntoIterator};
+use crate::strategy::{Action, Observation, Strategy};
+
+/// # Round
+/// The record of each submit round in a game
+#[derive(Debug, Clone)]
+pub struct Round {
+    /// The bond of each submission in this round
+    pub bond: f64,
+    /// The challenge time in Darwinia blocks of this round
+    pub challenge_time: usize,
+    /// The Ethereum block submitted in this round
+    pub submit_ethereum_block: usize,
+    /// The next Ethereum block should be submitted
+    pub target_ethereum_block: usize,
+    /// The relayers submit in this round, and they lie or not
+    pub submissions: Vec<(String, bool)>,
+    /// The challengers challenge in this round
+    pub challenges: Vec<String>,
+    /// The participators missed the challenge window in this round
+    pub missed: Vec<String>,
+    /// The relayers submitted the headers can not be validated in this round
+    pub rejected: Vec<String>,
+    /// The bond pool after this round
+    pub submit_bond_pool: f64,
+    /// The slash to share in this round, the slash carried from the previous round and the bonds
+    /// slashed in this round, less the slash carried to the next round, the bonds forfeited by the
+    /// lying relayers are settled after the game closed
+    pub slash: f64,
+    /// The slash shared in this round, the rewards from slash except the bonds returned, settled
+    /// after the game closed
+    pub shared: f64,
+    /// The balance earned by each participator after this round, the rewards are settled after
+    /// the game closed
+    pub balances: BTreeMap<String, f64>,
+}
+
+/// # Game Result
+/// The summary of a game
+#[derive(Debug, Default, Clone)]
+pub struct GameResult {
+    pub rounds: Vec<Round>,
+    /// The positions of each submission, used for plotting
+    pub relay_positions: RelayPositions,
+    /// The Darwinia blocks from the game start to the game end
+    pub duration: usize,
+    /// The time in seconds from the game start to the game end, only simulated with block time
+    pub elapsed_time: f64,
+    /// The max value of bond pool in this game
+    pub max_bond_value: f64,
+    /// The latest confirmed Ethereum block when the game closed
+    pub latest_confirm_ethereum_block: usize,
+    /// The Ethereum blocks confirmed in this game
+    pub confirmed_blocks: usize,
+    /// The slash left in the bond pool when the game closed, such as the slash carried by the
+    /// reward equation and the rewards forfeited, which is settled to the treasury
+    pub unclaimed_slash: f64,
+}
+
+/// # Game
+/// The status of a game in playing, the game can be played round by round with `step`
+#[derive(Clone)]
+pub struct Game {
+    iterator: ScenarioConfigIntoIterator,
+    challenge_eq: Box<dyn ChallengeEq>,
+    sample_eq: Box<dyn TargetEq>,
+    reward_eq: Box<dyn RewardEq>,
+    bond_eq: Box<dyn BondEq>,
+    darwinia_start_block: usize,
+    start_time: f64,
+    start_bond_pool: f64,
+    genesis: (usize, usize),
+    reward_actions: Vec<Reward>,
+    reward_from_previous_round: f64,
+    latest_confirm_ethereum_block: usize,
+    /// The bonds paid by each participator in this game, which will be returned
+    bonds: HashMap<String, f64>,
+    /// The participators missed the challenge window, they are deemed as no response
+    missed: HashSet<String>,
+    /// The challengers with adaptive strategies decide to challenge or not in each round
+    strategies: HashMap<String, Box<dyn Strategy>>,
+    /// The submissions of relayers in the previous round
+    previous: Vec<(String, bool)>,
+    /// The rewards from slash of each round as (participator, value) with the bond returned
+    round_rewards: Vec<Vec<(String, f64)>>,
+    result: GameResult,
+}
+
+impl Game {
+    /// Prepare a game with the equations and the choices in scenario config on the chains status
+    pub fn new(config: &ScenarioConfig, chains_status: &ChainsStatus) -> Result<Self, Error> {
+        let mut result = GameResult::default();
+        result.relay_positions.geneisis = chains_status.genesis.1;
+        result
+            .relay_positions
+            .relay_blocks
+            .push(vec![chains_status.submit_target_ethereum_block]);
+        Ok(Game {
+            iterator: config.get_iter(),
+            challenge_eq: config.get_challenge_equation()?,
+            sample_eq: Box::new(config.get_sample_equation()?),
+            reward_eq: config.get_reward_equation()?,
+            bond_eq: config.get_bond_equation()?,
+            darwinia_start_block: chains_status.darwinia_block_hight,
+            start_time: chains_status.elapsed_time,
+            start_bond_pool: chains_status.submit_bond_pool,
+            genesis: chains_status.genesis,
+            reward_actions: Vec::new(),
+            reward_from_previous_round: 0f64,
+            latest_confirm_ethereum_block: chains_status.genesis.1,
+            bonds: HashMap::new(),
+            missed: HashSet::new(),
+            strategies: config
+                .challengers
+                .iter()
+                .flatten()
+                .filter(|c| c.strategy.as_ref().map(|s| s.is_adaptive()) == Some(true))
+                .map(|c| (c.id().to_string(), c.build_strategy(Role::Challenger)))
+                .collect(),
+            previous: Vec::new(),
+            round_rewards: Vec::new(),
+            result,
+        })
+    }
+
+    /// The participator missed the challenge window or submitted a rejected header is deemed as
+    /// no response, the rewards are cancelled and the bonds in this game are slashed, return the
+    /// slash value
+    fn miss(&mut self, participator: String) -> f64 {
+        self.reward_actions.retain(|r| r.to != participator);
+        for rewards in self.round_rewards.iter_mut() {
+            rewards.retain(|r| r.0 != participator);
+        }
+        let slash = self.bonds.remove(&participator).unwrap_or_default();
+        self.missed.insert(participator);
+        slash
+    }
+
+    /// The challenger decides to challenge or not in the round with the submissions of relayers,
+    /// return None if it does not challenge, else whether it lies with the adaptive strategy.  The
+    /// challengers without adaptive strategies always challenge
+    fn will_challenge(
+        &mut self,
+        chains_status: &ChainsStatus,
+        challenger: &str,
+        bond: f64,
+        moves: &[(String, bool)],
+    ) -> Option<bool> {
+        // the reward of challenging the lying relayers, with the bond returned
+        let slash = moves.iter().filter(|m| m.1).count() as f64 * bond;
+        let reward = self
+            .reward_eq
+            .calculate(0.0, slash, bond, vec![challenger.to_string()])
+            .1
+            .iter()
+            .map(|r| r.value)
+            .sum();
+        match self.strategies.get_mut(challenger) {
+            Some(s) => match s.decide(&Observation {
+                round: self.iterator.submit_round,
+                reward,
+                moves: moves.to_vec(),
+                previous: self.previous.clone(),
+                ..chains_status.observe(challenger, bond)
+            }) {
+                Action::Honest => Some(false),
+                Action::Lie => Some(true),
+                Action::NoResponse => None,
+            },
+            None => Some(false),
+        }
+    }
+
+    /// Play the next submit round, return false if there is no more choice in the scenario
+    pub fn step(&mut self, chains_status: &mut ChainsStatus, debug: bool) -> bool {
+        let bond = self.bond_eq.calculate(self.iterator.submit_round + 1);
+        let observed: &ChainsStatus = chains_status;
+        let mut relayer_submissions =
+            match self.iterator.next_observed(|id| observed.observe(id, bond)) {
+                Some(s) => s,
+                None => return false,
+            };
+        let submition_times = chains_status.submitions.len();
+        let last_relayed_block = if submition_times > 0 {
+            chains_status.submitions[submition_times - 1]
+        } else {
+            self.genesis
+        };
+        let challenge_time = if last_relayed_block.1 > chains_status.submit_target_ethereum_block {
+            self.challenge_eq.calculate(
+                chains_status.darwinia_block_hight - last_relayed_block.0,
+                last_relayed_block.1 - chains_status.submit_target_ethereum_block,
+            )
+        } else {
+            self.challenge_eq.calculate(
+                chains_status.darwinia_block_hight - last_relayed_block.0,
+                chains_status.submit_target_ethereum_block - last_relayed_block.1,
+            )
+        };
+
+        // the submissions arrive after the challenge window closed are deemed as no response
+        let mut missed = Vec::new();
+        let missed_before = &self.missed;
+        relayer_submissions.retain(|(r, _)| {
+            if missed_before.contains(r) {
+                false
+            } else if chains_status.arrive_in_time(r, challenge_time) {
+                true
+            } else {
+                missed.push(r.clone());
+                false
+            }
+        });
+        // the headers not valid or contradictory with the submissions before are rejected
+        let mut rejected = Vec::new();
+        relayer_submissions.retain(|(r, lie)| {
+            if chains_status.submit_header(r, *lie) {
+                true
+            } else {
+                rejected.push(r.clone());
+                false
+            }
+        });
+        let mut missed_slash = 0f64;
+        for r in missed.iter().chain(rejected.iter()) {
+            missed_slash += self.miss(r.clone());
+        }
+        if relayer_submissions.is_empty() {
+            // the only relayer in relayer-challenger(s) mod stops responding
+            return false;
+        }
+
+        let total_lie_relayer = relayer_submissions.iter().filter(|r| r.1).count();
+        // the bonds of the lie relayer are rewarded to the challengers in relayer-challenger(s) mod
+        let lie_slash = if chains_status.challengers.is_empty() {
+            total_lie_relayer as f64 * bond
+        } else {
+            0.0
+        };
+        let current_slash = chains_status.route_slash(lie_slash + missed_slash);
+        let previous_slash = self.reward_from_previous_round;
+        let mut r = self.reward_eq.calculate(
+            self.reward_from_previous_round,
+            current_slash,
+            bond,
+            relayer_submissions
+                .iter()
+                .filter(|r| !r.1)
+                .map(|r| r.0.clone())
+                .collect(),
+        );
+        self.reward_from_previous_round = r.0;
+        let mut slash = previous_slash + current_slash - r.0;
+        let mut rewards: Vec<(String, f64)> =
+            r.1.iter()
+                .filter(|r| matches!(r.from, RewardFrom::Slash))
+                .map(|r| (r.to.clone(), r.value))
+                .collect();
+        self.reward_actions.append(&mut r.1);
+
+        if debug {
+            print!("{}", chains_status.fmt_status().cyan());
+            println!("\tSubmission Plot: {}", self.result.relay_positions.plot());
+            print!("\tSubmission(Bond: {}): ", bond);
+            for (r, lie) in relayer_submissions.iter() {
+                print!("{}", r);
+                if *lie {
+                    print!("(lie)");
+                } else {
+                    print!("(honest)");
+                }
+                print!(" ");
+            }
+            println!();
+            if !missed.is_empty() {
+                println!("\tMissed Challenge Window: {}", missed.join(", "));
+            }
+            if !rejected.is_empty() {
+                println!("\tRejected Header: {}", rejected.join(", "));
+            }
+        }
+
+        let target_block = if 0 == total_lie_relayer {
+            self.latest_confirm_ethereum_block = chains_status.submit_target_ethereum_block;
+            self.sample_eq.calculate(
+                chains_status.submit_target_ethereum_block,
+                last_relayed_block.1,
+            )
+        } else {
+            self.sample_eq.calculate(
+                self.latest_confirm_ethereum_block,
+                chains_status.submit_target_ethereum_block,
+            )
+        };
+
+        let moves = relayer_submissions.clone();
+        let mut relay_blocks = Vec::new();
+        let mut challenges = Vec::new();
+        if !chains_status.challengers.is_empty() {
+            slash += total_lie_relayer as f64 * bond;
+        }
+        if chains_status.challengers.len() == 1 {
+            // relayer-challenger mod
+            for (challenger, _) in chains_status.challengers.clone().iter() {
+                if self.missed.contains(challenger) {
+                    continue;
+                }
+                let lie = match self.will_challenge(chains_status, challenger, bond, &moves) {
+                    Some(lie) => lie,
+                    None => continue,
+                };
+                if !chains_status.arrive_in_time(challenger, challenge_time) {
+                    missed.push(challenger.clone());
+                    let slash = self.miss(challenger.clone());
+                    self.reward_from_previous_round += chains_status.route_slash(slash);
+                    continue;
+                }
+                chains_status.challenge_by(challenger.clone(), bond);
+                *self.bonds.entry(challenger.clone()).or_default() += bond;
+                challenges.push(challenger.clone());
+                // the lying challenge only gets the bond returned
+                let value = if lie { bond } else { bond * 2.0 };
+                rewards.push((challenger.clone(), value));
+                self.reward_actions.push(Reward {
+                    from: RewardFrom::Slash,
+                    to: challenger.clone(),
+                    value,
+                });
+            }
+        } else if chains_status.challengers.len() > 1 {
+            // relayer-challengers mod
+            let relayer = relayer_submissions[0].0.clone();
+            let mut is_additional_challenge = false;
+            for (challenger, obj) in chains_status.challengers.clone().iter() {
+                if obj.submit_round == submition_times + 1 {
+                    if self.missed.contains(challenger) {
+                        continue;
+                    }
+                    let lie = match self.will_challenge(chains_status, challenger, bond, &moves) {
+                        Some(lie) => lie,
+                        None => continue,
+                    };
+                    if !chains_status.arrive_in_time(challenger, challenge_time) {
+                        missed.push(challenger.clone());
+                        let slash = self.miss(challenger.clone());
+                        self.reward_from_previous_round += chains_status.route_slash(slash);
+                        continue;
+                    }
+                    if is_additional_challenge {
+                        // the bond of the additional submission is not returned
+                        slash += bond;
+                        relayer_submissions.push((relayer.clone(), false));
+                        if total_lie_relayer == 0 {
+                            relay_blocks.push(
+                                chains_status.submit_target_ethereum_block * 2
+                                    - (chains_status.submit_target_ethereum_block
+                                        + last_relayed_block.1)
+                                        / 2,
+                            );
+                        } else {
+                            relay_blocks.push(
+                                (chains_status.submit_target_ethereum_block + last_relayed_block.1)
+                                    / 2,
+                            );
+                        }
+                    }
+
+                    chains_status.challenge_by(challenger.clone(), bond);
+                    *self.bonds.entry(challenger.clone()).or_default() += bond;
+                    challenges.push(challenger.clone());
+                    if obj.lie || lie {
+                        // We can not sure the challenge is lie or not, so we return the bond
+                        rewards.push((challenger.clone(), bond));
+                        self.reward_actions.push(Reward {
+                            from: RewardFrom::Slash,
+                            to: challenger.clone(),
+                            value: bond,
+                        });
+                    } else {
+                        rewards.push((challenger.clone(), bond * 2.0));
+                        self.reward_actions.push(Reward {
+                            from: RewardFrom::Slash,
+                            to: challenger.clone(),
+                            value: bond * 2.0,
+                        });
+                    }
+
+                    is_additional_challenge = true;
+                }
+            }
+        }
+
+        self.previous = moves;
+
+        // the bonds of lie submissions are already slashed
+        for (r, _) in relayer_submissions.iter().filter(|r| !r.1) {
+            *self.bonds.entry(r.clone()).or_default() += bond;
+        }
+        let submit_ethereum_block = chains_status.submit_target_ethereum_block;
+        let elapsed_time = chains_status.elapsed_time;
+        chains_status.submit(
+            relayer_submissions.clone(),
+            bond,
+            challenge_time,
+            target_block,
+        );
+
+        relay_blocks.push(chains_status.submit_target_ethereum_block);
+        self.result.relay_positions.relay_blocks.push(relay_blocks);
+
+        // TODO: make this as an option
+        chains_status.should_balance();
+
+        if debug {
+            println!(
+                "\tNext Etherem Target Block: {}",
+                chains_status.submit_target_ethereum_block
+            );
+            if chains_status.block_producer.is_some() {
+                println!(
+                    "\tChallenge Time: {} blocks ({} seconds)",
+                    challenge_time,
+                    chains_status.elapsed_time - elapsed_time
+                );
+            } else {
+                println!("\tChallenge Time: {} blocks", challenge_time);
+            }
+            println!("\tRelayer Status: {}", chains_status.fmt_relayers_status());
+            println!(
+                "\tChallenger Status: {}",
+                chains_status.fmt_challengers_status()
+            );
+            println!(
+                "\tSubmit Bond Pool Status: {}",
+                chains_status.submit_bond_pool
+            );
+        }
+
+        self.result.rounds.push(Round {
+            bond,
+            challenge_time,
+            submit_ethereum_block,
+            target_ethereum_block: chains_status.submit_target_ethereum_block,
+            submissions: relayer_submissions,
+            challenges,
+            missed,
+            rejected,
+            submit_bond_pool: chains_status.submit_bond_pool,
+            slash,
+            shared: 0.0,
+            balances: chains_status
+                .relayers
+                .iter()
+                .chain(chains_status.challengers.iter())
+                .map(|(id, s)| (id.clone(), s.balance() - s.initial_balance))
+                .collect(),
+        });
+        self.round_rewards.push(rewards);
+        true
+    }
+
+    /// The rounds played so far in the game
+    pub fn result(&self) -> &GameResult {
+        &self.result
+    }
+
+    /// Close the game, settle the reward and collect the fee for the confirmed blocks
+    pub fn finish(mut self, chains_status: &mut ChainsStatus) -> GameResult {
+        self.result.max_bond_value = chains_status.submit_bond_pool - self.start_bond_pool;
+        for (round, rewards) in self.result.rounds.iter_mut().zip(self.round_rewards) {
+            for (to, value) in rewards {
+                // the lying relayers forfeit the rewards with their bonds
+                match chains_status.relayers.get(&to) {
+                    Some(r) if r.lie => round.slash += round.bond,
+                    _ => round.shared += value - round.bond,
+                }
+            }
+        }
+        chains_status.reward(self.reward_actions);
+        self.result.unclaimed_slash = chains_status.submit_bond_pool - self.start_bond_pool;
+        chains_status.settle_slash(self.result.unclaimed_slash);
+
+        self.result.latest_confirm_ethereum_block = self.latest_confirm_ethereum_block;
+        self.result.confirmed_blocks = self
+            .latest_confirm_ethereum_block
+            .saturating_sub(self.genesis.1);
+        chains_status
+            .treasury
+            .collect_fee(self.result.confirmed_blocks);
+        self.result.duration = chains_status.darwinia_block_hight - self.darwinia_start_block;
+        self.result.elapsed_time = chains_status.elapsed_time - self.start_time;
+        self.result
+    }
+}
+
+/// Play a game with the equations and the choices in scenario config on the chains status,
+/// and settle the reward when the game closed.
+pub fn play(
+    config: &ScenarioConfig,
+    chains_status: &mut ChainsStatus,
+    debug: bool,
+) -> Result<GameResult, Error> {
+    let mut game = Game::new(config, chains_status)?;
+    while game.step(chains_status, debug) {}
+    Ok(game.finish(chains_status))
+}
+
+/// Play a game of the scenario from the genesis, and return the chains status settled with the
+/// game result, the patched scenarios can be played without the command line
+pub fn run(config: &ScenarioConfig) -> Result<(ChainsStatus, GameResult), Error> {
+    let mut chains_status: ChainsStatus = config.clone().into();
+    let result = play(config, &mut chains_status, false)?;
+    Ok((chains_status, result))
+}
+
+/// # Concurrent Result
+/// The summary of the games played concurrently
+#[derive(Debug, Default)]
+pub struct ConcurrentResult {
+    /// The result of each game, keyed by the disputed Ethereum block
+    pub games: Vec<(usize, GameResult)>,
+    /// The Darwinia blocks from the first game start to the last game end
+    pub duration: usize,
+    /// The max bond locked in all the games at the same time for each participator
+    pub max_locked_bonds: BTreeMap<String, f64>,
+}
+
+/// Play the games concurrently, the first scenario is the game already in chains status, and the
+/// others will be opened on chain.  The round with earliest challenge timer is played first.
+pub fn play_concurrent(
+    configs: &[ScenarioConfig],
+    chains_status: &mut ChainsStatus,
+    debug: bool,
+) -> Result<ConcurrentResult, Error> {
+    let mut games = Vec::new();
+    for (i, c) in configs.iter().enumerate() {
+        let disputed_block = if i == 0 {
+            chains_status.current_game
+        } else {
+            chains_status.open_game(c)
+        };
+        chains_status.switch_game(disputed_block)?;
+        games.push((disputed_block, Some(Game::new(c, chains_status)?)));
+    }
+    let darwinia_start_block = games
+        .iter()
+        .map(|g| chains_status.game_clock(g.0))
+        .min()
+        .unwrap_or_default();
+
+    let mut result = ConcurrentResult::default();
+    let mut locked_bonds: HashMap<usize, HashMap<String, f64>> = HashMap::new();
+    while let Some(i) = games
+        .iter()
+        .enumerate()
+        .filter(|(_, g)| g.1.is_some())
+        .min_by_key(|(_, g)| chains_status.game_clock(g.0))
+        .map(|(i, _)| i)
+    {
+        let disputed_block = games[i].0;
+        chains_status.switch_game(disputed_block)?;
+        let pays: HashMap<String, f64> = chains_status
+            .relayers
+            .iter()
+            .map(|(n, p)| (n.clone(), p.pay))
+            .collect();
+        if debug {
+            println!("Game on Ethereum #{}", disputed_block);
+        }
+        if games[i].1.as_mut().unwrap().step(chains_status, debug) {
+            let locked = locked_bonds.entry(disputed_block).or_default();
+            for (name, p) in chains_status.relayers.iter() {
+                *locked.entry(name.clone()).or_default() += p.pay - pays[name];
+            }
+            for name in chains_status.relayers.keys() {
+                let total: f64 = locked_bonds
+                    .values()
+                    .map(|l| l.get(name).cloned().unwrap_or_default())
+                    .sum();
+                let max = result.max_locked_bonds.entry(name.clone()).or_default();
+                *max = max.max(total);
+            }
+        } else {
+            let game = games[i].1.take().unwrap();
+            result
+                .games
+                .push((disputed_block, game.finish(chains_status)));
+            locked_bonds.remove(&disputed_block);
+        }
+    }
+
+    // show the status of the last closed game
+    if let Some(last) = games.iter().max_by_key(|g| chains_status.game_clock(g.0)) {
+        chains_status.switch_game(last.0)?;
+    }
+    result.duration = chains_status.darwinia_block_hight - darwinia_start_block;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scenario::{GameConfig, RelayerConfig};
+    use crate::strategy::Scripted;
+    use std::str::FromStr;
+    static TOML_CONFIG: &'static str = r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10.0"
+			reward_function = "split"
+
+			Dd = 100
+			De = 1000
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LL"
+			"#;
+    #[test]
+    fn test_play_with_strategies() {
+        let config = <ScenarioConfig>::from_str(&format!(
+            "{}{}",
+            TOML_CONFIG
+                .replace(r#"choice = "LL""#, "")
+                .replace(r#"bond_function = "10.0""#, r#"bond_function = "linear""#),
+            r#"
+			strategy = { type = "adaptive", X = 10.0, rounds = 3 }
+
+			[[relayers]]
+			name = "Mirror"
+			strategy = { type = "adaptive", rule = "tit_for_tat", rounds = 3 }
+
+			[bond_linear]
+			W = 10.0
+			C = 0.0
+			M = 100.0
+			"#
+        ))
+        .unwrap();
+        let mut c: ChainsStatus = config.clone().into();
+        let result = play(&config, &mut c, false).unwrap();
+        let submissions: Vec<Vec<(&str, bool)>> = result
+            .rounds
+            .iter()
+            .map(|r| r.submissions.iter().map(|s| (s.0.as_str(), s.1)).collect())
+            .collect();
+        assert_eq!(submissions[0][1..], [("Evil", true), ("Mirror", false)]);
+        assert_eq!(submissions[1][1..], [("Evil", false), ("Mirror", true)]);
+        assert_eq!(submissions[2][1..], [("Evil", false), ("Mirror", false)]);
+
+        let config = <ScenarioConfig>::from_str(&format!(
+            "{}{}",
+            TOML_CONFIG.replace("LL", "LHL"),
+            r#"
+			[[challengers]]
+			name = "Watcher"
+			strategy = { type = "adaptive", rule = "profitable" }
+			"#
+        ))
+        .unwrap();
+        let mut c: ChainsStatus = config.clone().into();
+        play(&config, &mut c, false).unwrap();
+        assert_eq!(c.challengers["Watcher"].pay, 20.0);
+
+        // no reward is left to the challenger from the reward equation
+        let mut config = config;
+        config.apply_patch(vec!["reward_split.P=0"]).unwrap();
+        let mut c: ChainsStatus = config.clone().into();
+        play(&config, &mut c, false).unwrap();
+        assert_eq!(c.challengers["Watcher"].pay, 0.0);
+
+        // the lying challenge only gets the bond returned
+        let config = <ScenarioConfig>::from_str(&format!(
+            "{}{}",
+            TOML_CONFIG,
+            r#"
+			[[challengers]]
+			name = "Watcher"
+			strategy = { type = "adaptive", rule = "tit_for_tat" }
+			"#
+        ))
+        .unwrap();
+        let mut c: ChainsStatus = config.clone().into();
+        let mut game = Game::new(&config, &c).unwrap();
+        let watcher = c.challengers.keys().next().unwrap().clone();
+        game.strategies
+            .insert(watcher.clone(), Box::new(Scripted::from("LL")));
+        while game.step(&mut c, false) {}
+        game.finish(&mut c);
+        assert_eq!(c.challengers[&watcher].pay, 20.0);
+        assert_eq!(
+            c.challengers[&watcher].balance(),
+            c.challengers[&watcher].initial_balance
+        );
+    }
+    #[test]
+    fn test_settle_unclaimed_slash() {
+        let config =
+            <ScenarioConfig>::from_str(&TOML_CONFIG.replace(r#""LL""#, r#""LH""#)).unwrap();
+        let mut c: ChainsStatus = config.clone().into();
+        let result = play(&config, &mut c, false).unwrap();
+        // the bond of Evil in the second round is forfeited for the lie in the first round
+        assert_eq!(result.unclaimed_slash, 10.0);
+        assert_eq!(c.submit_bond_pool, 0.0);
+        assert_eq!(c.treasury.balance, 10.0);
+        let ledger: Vec<(f64, f64)> = result.rounds.iter().map(|r| (r.slash, r.shared)).collect();
+        assert_eq!(ledger, vec![(10.0, 10.0), (10.0, 0.0), (0.0, 0.0)]);
+    }
+    #[test]
+    fn test_play_games() {
+        let config = <ScenarioConfig>::from_str(TOML_CONFIG).unwrap();
+        let sample_eq = config.get_sample_equation().unwrap();
+        let mut c: ChainsStatus = config.clone().into();
+        let result = play(&config, &mut c, false).unwrap();
+        assert_eq!(result.rounds.len(), 3);
+        assert_eq!(result.duration, 30);
+        assert_eq!(result.max_bond_value, 50.0);
+        assert_eq!(c.relayers["Evil"].balance(), -20.0);
+        assert_eq!(c.relayers["Darwinia"].balance(), 20.0);
+
+        c.next_game(&sample_eq, result.latest_confirm_ethereum_block);
+        assert_eq!(c.genesis, (130, result.latest_confirm_ethereum_block));
+        let result = play(&config, &mut c, false).unwrap();
+        assert_eq!(result.rounds.len(), 3);
+        c.should_balance();
+        assert_eq!(c.relayers["Evil"].balance(), -40.0);
+        assert_eq!(c.relayers["Darwinia"].balance(), 40.0);
+    }
+    #[test]
+    fn test_miss_challenge_window() {
+        // the challenge windows are 61, 32, 16, 8 blocks
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "linear"
+			target_function = "half"
+			bond_function = "10.0"
+			reward_function = "split"
+
+			Dd = 100
+			De = 1000
+
+			[challenge_linear]
+			Wd = 0.1
+			We = 0.1
+			C  = 1
+			Md = 1000
+			Me = 1000
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Slow"
+			choice = "HHH"
+			latency = { model = "fixed", mean = 30.0 }
+			"#,
+        )
+        .unwrap();
+        let mut c: ChainsStatus = config.clone().into();
+        let result = play(&config, &mut c, false).unwrap();
+        assert_eq!(result.rounds[1].submissions.len(), 2);
+        assert_eq!(result.rounds[2].missed, vec!["Slow".to_string()]);
+        assert_eq!(result.rounds[2].submissions.len(), 1);
+        c.should_balance();
+        assert_eq!(c.relayers["Slow"].balance(), -20.0);
+        assert_eq!(c.relayers["Darwinia"].balance(), 20.0);
+    }
+    #[test]
+    fn test_reject_contradictory_header() {
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10.0"
+			reward_function = "split"
+
+			De = 16
+
+			[reward_split]
+			P = 1.0
+
+			[header]
+			D = 2048000
+			T = 13
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LLLH"
+			"#,
+        )
+        .unwrap();
+        let mut c: ChainsStatus = config.clone().into();
+        let result = play(&config, &mut c, false).unwrap();
+        // the honest header of Evil is adjacent to the forged one
+        assert_eq!(result.rounds[3].rejected, vec!["Evil".to_string()]);
+        assert!(c.submit_headers[&8].len() > 1);
+        c.should_balance();
+        assert_eq!(c.relayers["Evil"].balance(), -30.0);
+        assert_eq!(c.relayers["Darwinia"].balance(), 30.0);
+    }
+    #[test]
+    fn test_play_concurrent_games() {
+        let mut config = <ScenarioConfig>::from_str(TOML_CONFIG).unwrap();
+        config.games = Some(vec![GameConfig {
+            Dd: Some(100),
+            De: Some(2000),
+            relayers: vec![RelayerConfig {
+                name: Some("Evil".to_string()),
+                choice: "L".to_string(),
+                ..Default::default()
+            }],
+        }]);
+        let mut configs = vec![config.clone()];
+        configs.append(&mut config.get_concurrent_scenarios().unwrap());
+        let mut c: ChainsStatus = config.into();
+        let result = play_concurrent(&configs, &mut c, false).unwrap();
+        assert_eq!(result.games.len(), 2);
+        assert_eq!(result.games[0].0, 1000);
+        assert_eq!(result.duration, 30);
+        assert_eq!(result.max_locked_bonds["Evil"], 30.0);
+        assert_eq!(result.max_locked_bonds["Darwinia"], 50.0);
+        c.should_balance();
+        assert_eq!(c.relayers["Evil"].balance(), -30.0);
+        assert_eq!(c.relayers["Darwinia"].balance(), 30.0);
+    }
+    #[test]
+    fn test_play_concurrent_games_lie_in_one() {
+        let mut config = <ScenarioConfig>::from_str(&TOML_CONFIG.replace(
+            r#"name = "Evil""#,
+            r#"name = "Mixed""#,
+        ))
+        .unwrap();
+        config.relayers[1].choice = "HHH".to_string();
+        config.games = Some(vec![GameConfig {
+            Dd: Some(100),
+            De: Some(2000),
+            relayers: vec![RelayerConfig {
+                name: Some("Mixed".to_string()),
+                choice: "L".to_string(),
+                ..Default::default()
+            }],
+        }]);
+        let mut configs = vec![config.clone()];
+        configs.append(&mut config.get_concurrent_scenarios().unwrap());
+        let mut c: ChainsStatus = config.into();
+        play_concurrent(&configs, &mut c, false).unwrap();
+        c.should_balance();
+        // Mixed is rewarded in the honest game, and slashed in the other
+        let pools: f64 = c.games.values().map(|g| g.submit_bond_pool).sum();
+        assert_eq!(c.submit_bond_pool + pools, 0.0);
+        assert_eq!(c.relayers["Mixed"].balance(), -10.0);
+        assert_eq!(c.relayers["Darwinia"].balance(), 10.0);
+    }
+}
@@ -0,0 +1,215 @@
+//! Golden module
+//! Play every scenario in `scenario/` and compare the structured results with the expected
+//! outputs checked in `refit/tests/golden/`, such that the changes of the equations can not shift
+//! the payouts silently.
+//!
+//! The expected outputs are re-blessed with the environment variable `REFIT_BLESS`, and the
+//! changes should be reviewed before committing.
+//! ```sh
+//! REFIT_BLESS=1 cargo test golden
+//! ```
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use crate::chain::{ChainsStatus, ParticipatorStatus};
+use crate::error::Error;
+use crate::game::{play, play_concurrent, GameResult};
+use crate::patch::join_key;
+use crate::scenario::ScenarioConfig;
+
+/// The numbers relatively closer than this are deemed as the same
+static TOLERANCE: f64 = 0.000_000_001;
+
+fn fmt_game(result: &GameResult) -> Value {
+    json!({
+        "duration": result.duration,
+        "elapsed_time": result.elapsed_time,
+        "max_bond_value": result.max_bond_value,
+        "latest_confirm_ethereum_block": result.latest_confirm_ethereum_block,
+        "confirmed_blocks": result.confirmed_blocks,
+        "rounds": result.rounds.iter().map(|r| json!({
+            "bond": r.bond,
+            "challenge_time": r.challenge_time,
+            "submit_ethereum_block": r.submit_ethereum_block,
+            "target_ethereum_block": r.target_ethereum_block,
+            "submissions": r.submissions,
+            "missed": r.missed,
+            "rejected": r.rejected,
+            "submit_bond_pool": r.submit_bond_pool,
+        })).collect::<Vec<Value>>(),
+    })
+}
+
+fn fmt_participators(participators: &HashMap<String, ParticipatorStatus>) -> Value {
+    participators
+        .iter()
+        .map(|(id, s)| {
+            (
+                id.clone(),
+                json!({
+                    "balance": s.balance(),
+                    "pay": s.pay,
+                    "reward": [s.reward.0, s.reward.1],
+                    "fee": s.fee,
+                }),
+            )
+        })
+        .collect::<serde_json::Map<String, Value>>()
+        .into()
+}
+
+fn fmt_chains_status(chains_status: &ChainsStatus) -> Value {
+    json!({
+        "relayers": fmt_participators(&chains_status.relayers),
+        "challengers": fmt_participators(&chains_status.challengers),
+        "treasury": {
+            "balance": chains_status.treasury.balance,
+            "paid": chains_status.treasury.paid,
+        },
+        "transaction_fee": chains_status.transaction_fee,
+    })
+}
+
+/// Play the scenario and record the structured result
+pub fn record(config: &ScenarioConfig) -> Result<Value, Error> {
+    let mut chains_status: ChainsStatus = config.clone().into();
+    let games = if config.games.is_some() {
+        let mut configs = vec![config.clone()];
+        configs.append(&mut config.get_concurrent_scenarios()?);
+        let result = play_concurrent(&configs, &mut chains_status, false)?;
+        result
+            .games
+            .iter()
+            .map(|(disputed_block, r)| {
+                let mut game = fmt_game(r);
+                game["disputed_block"] = json!(disputed_block);
+                game
+            })
+            .collect()
+    } else {
+        vec![fmt_game(&play(config, &mut chains_status, false)?)]
+    };
+    Ok(json!({
+        "games": games,
+        "status": fmt_chains_status(&chains_status),
+    }))
+}
+
+/// Compare the values, and push the paths of the differences
+fn compare(expected: &Value, actual: &Value, path: String, diffs: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Number(e), Value::Number(a)) => {
+            let (e, a) = (e.as_f64().unwrap(), a.as_f64().unwrap());
+            if (e - a).abs() > TOLERANCE * e.abs().max(a.abs()).max(1.0) {
+                diffs.push(format!("{}: expected {}, found {}", path, e, a));
+            }
+        }
+        (Value::Array(e), Value::Array(a)) if e.len() == a.len() => {
+            for (i, (e, a)) in e.iter().zip(a.iter()).enumerate() {
+                compare(e, a, format!("{}[{}]", path, i), diffs);
+            }
+        }
+        (Value::Object(e), Value::Object(a)) => {
+            for (k, e) in e.iter() {
+                let p = join_key(path.clone(), k);
+                match a.get(k) {
+                    Some(a) => compare(e, a, p, diffs),
+                    None => diffs.push(format!("{}: missing", p)),
+                }
+            }
+            for k in a.keys().filter(|k| !e.contains_key(*k)) {
+                diffs.push(format!("{}: unexpected", join_key(path.clone(), k)));
+            }
+        }
+        _ if expected == actual => {}
+        _ => diffs.push(format!("{}: expected {}, found {}", path, expected, actual)),
+    }
+}
+
+/// The scenario files in the directory
+fn scenarios(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.is_file()
+                && matches!(
+                    p.extension().and_then(|e| e.to_str()),
+                    Some("toml") | Some("yml") | Some("yaml") | Some("json")
+                )
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_golden_files() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let golden = root.join("tests").join("golden");
+        let bless = std::env::var_os("REFIT_BLESS").is_some();
+        let mut failures = Vec::new();
+        for file in scenarios(&root.join("..").join("scenario")) {
+            let name = file.file_stem().unwrap().to_str().unwrap();
+            let actual =
+                match ScenarioConfig::from_file(file.to_str().unwrap()).and_then(|c| record(&c)) {
+                    Ok(v) => v,
+                    Err(e) => json!({ "error": e.to_string() }),
+                };
+            let expected_file = golden.join(format!("{}.json", name));
+            if bless {
+                fs::create_dir_all(&golden).unwrap();
+                fs::write(
+                    &expected_file,
+                    serde_json::to_string_pretty(&actual).unwrap() + "\n",
+                )
+                .unwrap();
+                continue;
+            }
+            let expected: Value = match fs::read_to_string(&expected_file) {
+                Ok(s) => serde_json::from_str(&s).unwrap(),
+                Err(_) => {
+                    failures.push(format!("{}: no expected output", name));
+                    continue;
+                }
+            };
+            let mut diffs = Vec::new();
+            compare(&expected, &actual, String::new(), &mut diffs);
+            failures.extend(diffs.into_iter().map(|d| format!("{}: {}", name, d)));
+        }
+        assert!(
+            failures.is_empty(),
+            "the results differ from the golden files, run with REFIT_BLESS=1 to re-bless them \
+             if the changes are expected\n{}",
+            failures.join("\n")
+        );
+    }
+    #[test]
+    fn test_compare() {
+        let expected = json!({"a": [1.0, 2.0], "b": "x"});
+        let mut diffs = Vec::new();
+        compare(
+            &expected,
+            &json!({"a": [1.0, 2.000_000_000_001], "b": "x"}),
+            String::new(),
+            &mut diffs,
+        );
+        assert!(diffs.is_empty());
+        compare(
+            &expected,
+            &json!({"a": [1.0, 3.0], "c": "x"}),
+            String::new(),
+            &mut diffs,
+        );
+        assert_eq!(
+            diffs,
+            vec!["a[1]: expected 2, found 3", "b: missing", "c: unexpected"]
+        );
+    }
+}
@@ -0,0 +1,385 @@
+//! Header module
+//! The submissions are simulated as block numbers by default, with the header model, the target
+//! chain is a synthetic chain of headers with parent hash, difficulty and timestamp.  The honest
+//! relayers submit the canonical headers, and the evil relayers submit the headers forged on their
+//! own branch from the last confirmed block.
+//!
+//! The chain validates the header and checks the header is not contradictory with the headers
+//! submitted by the same relayer, as the `validate(relayer, header, submit_headers)` in
+//! `pseudo/relayers-only/chain.md`.
+//!
+//! The canonical headers can also be imported from the JSON dumps of Ethereum headers, as
+//! returned by `eth_getBlockByNumber`, such that the simulation replays the real history.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+
+use serde_derive::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::Error;
+
+/// # Header Config
+/// The synthetic target chain, the difficulty of each block is slightly changed from D, and
+/// the block time is about T
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Copy, Clone)]
+pub struct HeaderConfig {
+    /// D: the base difficulty of the target chain
+    D: u64,
+    /// T: the block time of the target chain in seconds
+    T: u64,
+}
+
+impl HeaderConfig {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.D < 2048 {
+            return Err(Error::field("D", "should not be less than 2048"));
+        }
+        if self.T == 0 {
+            return Err(Error::field("T", "should be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// # Header
+/// The header of target chain, the extra data is used to forge a header with a different hash
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    pub number: usize,
+    pub parent_hash: u64,
+    pub difficulty: u64,
+    pub timestamp: u64,
+    pub extra_data: String,
+    pub hash: u64,
+}
+
+impl Header {
+    pub fn new(
+        number: usize,
+        parent_hash: u64,
+        difficulty: u64,
+        timestamp: u64,
+        extra_data: String,
+    ) -> Self {
+        let mut h = Header {
+            number,
+            parent_hash,
+            difficulty,
+            timestamp,
+            extra_data,
+            hash: 0,
+        };
+        h.hash = h.compute_hash();
+        h
+    }
+    pub fn compute_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.number.hash(&mut hasher);
+        self.parent_hash.hash(&mut hasher);
+        self.difficulty.hash(&mut hasher);
+        self.timestamp.hash(&mut hasher);
+        self.extra_data.hash(&mut hasher);
+        hasher.finish()
+    }
+    /// Basic block information check, the hash is correct and the difficulty is in the bound of
+    /// adjustment if the parent is known, the difficulty can be adjusted at most 99 steps of
+    /// parent difficulty / 2048 in each block as Ethereum
+    pub fn is_valid(&self, parent: Option<&Header>) -> bool {
+        if self.hash != self.compute_hash() {
+            return false;
+        }
+        if let Some(p) = parent {
+            let bound = p.difficulty / 2048 * 99;
+            if self.parent_hash != p.hash
+                || self.timestamp <= p.timestamp
+                || self.difficulty > p.difficulty + bound
+                || self.difficulty < p.difficulty - bound
+            {
+                return false;
+            }
+        }
+        true
+    }
+    /// The two headers submitted by the same relayer can not be on the same chain
+    pub fn contradicts(&self, other: &Header) -> bool {
+        if self.number == other.number {
+            return self.hash != other.hash;
+        }
+        let (low, high) = if self.number < other.number {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        if low.timestamp >= high.timestamp {
+            return true;
+        }
+        high.number == low.number + 1 && !high.is_valid(Some(low))
+    }
+}
+
+/// Parse the Ethereum headers from the JSON dumps, the dumps can be a list of the blocks or the
+/// responses of `eth_getBlockByNumber`.  The headers should be continuous, and the hash of
+/// Ethereum is kept in the extra data of header.
+pub fn parse_headers(s: &str) -> Result<Vec<Header>, Error> {
+    fn field(block: &Value, key: &str) -> Result<String, Error> {
+        block[key]
+            .as_str()
+            .map(|v| v.trim_start_matches("0x").to_string())
+            .ok_or_else(|| Error::HeaderFixtureError(format!("`{}` is absent", key)))
+    }
+    fn number(block: &Value, key: &str) -> Result<u64, Error> {
+        let v = field(block, key)?;
+        u64::from_str_radix(&v, 16)
+            .map_err(|_| Error::HeaderFixtureError(format!("`{}` is not a hex number", key)))
+    }
+
+    let value = serde_json::from_str(s).map_err(|e| Error::HeaderFixtureError(format!("{}", e)))?;
+    let blocks = match value {
+        Value::Array(blocks) => blocks,
+        block => vec![block],
+    };
+    let mut blocks = blocks
+        .into_iter()
+        .map(|b| if b["result"].is_object() { b["result"].clone() } else { b })
+        .collect::<Vec<Value>>();
+    blocks.sort_by_key(|b| number(b, "number").unwrap_or_default());
+
+    let mut headers: Vec<Header> = Vec::new();
+    for (i, b) in blocks.iter().enumerate() {
+        let n = number(b, "number")? as usize;
+        let parent_hash = match headers.last() {
+            Some(p) => {
+                if n != p.number + 1 {
+                    return Err(Error::HeaderFixtureError(format!(
+                        "the headers are not continuous at block #{}",
+                        n
+                    )));
+                }
+                if field(b, "parentHash")? != field(&blocks[i - 1], "hash")? {
+                    return Err(Error::HeaderFixtureError(format!(
+                        "the parent hash of block #{} is not correct",
+                        n
+                    )));
+                }
+                p.hash
+            }
+            None => 0,
+        };
+        headers.push(Header::new(
+            n,
+            parent_hash,
+            number(b, "difficulty")?,
+            number(b, "timestamp")?,
+            field(b, "hash")?,
+        ));
+    }
+    if headers.is_empty() {
+        return Err(Error::HeaderFixtureError("no header".to_string()));
+    }
+    Ok(headers)
+}
+
+/// # Header Chain
+/// The canonical headers of the target chain, and the forged headers of evil relayers
+#[derive(Debug, Clone)]
+pub struct HeaderChain {
+    difficulty: u64,
+    block_time: u64,
+    /// The block number of the first canonical header
+    first: usize,
+    canonical: Vec<Header>,
+    forged: HashMap<String, BTreeMap<usize, Header>>,
+}
+
+impl From<HeaderConfig> for HeaderChain {
+    fn from(c: HeaderConfig) -> Self {
+        HeaderChain {
+            difficulty: c.D,
+            block_time: c.T,
+            first: 0,
+            canonical: vec![Header::new(0, 0, c.D, 0, String::new())],
+            forged: HashMap::new(),
+        }
+    }
+}
+
+impl From<Vec<Header>> for HeaderChain {
+    /// The canonical chain of the imported headers, the headers after the last one are synthetic
+    fn from(headers: Vec<Header>) -> Self {
+        let first = &headers[0];
+        let last = &headers[headers.len() - 1];
+        let block_time = if headers.len() > 1 {
+            (last.timestamp - first.timestamp) / (headers.len() as u64 - 1)
+        } else {
+            13
+        };
+        HeaderChain {
+            difficulty: last.difficulty,
+            block_time: block_time.max(1),
+            first: first.number,
+            canonical: headers,
+            forged: HashMap::new(),
+        }
+    }
+}
+
+impl HeaderChain {
+    /// The canonical header of the block number
+    pub fn header(&mut self, number: usize) -> Header {
+        while self.first + self.canonical.len() <= number {
+            let parent = &self.canonical[self.canonical.len() - 1];
+            let n = parent.number + 1;
+            let header = Header::new(
+                n,
+                parent.hash,
+                self.difficulty + (n as u64 * 7) % (self.difficulty / 2048).max(1),
+                parent.timestamp + self.block_time + (n as u64 * 3) % self.block_time,
+                String::new(),
+            );
+            self.canonical.push(header);
+        }
+        self.canonical[number - self.first].clone()
+    }
+    /// The header forged by the relayer, the forged headers are on the branch forked from the
+    /// last confirmed block
+    pub fn forge(&mut self, relayer: &str, number: usize, fork: usize) -> Header {
+        if number <= fork {
+            return self.header(number);
+        }
+        let mut parent = self.header(fork);
+        for n in fork + 1..=number {
+            let cached = self
+                .forged
+                .get(relayer)
+                .and_then(|b| b.get(&n))
+                .filter(|h| h.parent_hash == parent.hash)
+                .cloned();
+            parent = match cached {
+                Some(h) => h,
+                None => {
+                    let canonical = self.header(n);
+                    let h = Header::new(
+                        n,
+                        parent.hash,
+                        canonical.difficulty,
+                        parent.timestamp.max(canonical.timestamp) + 1,
+                        relayer.to_string(),
+                    );
+                    self.forged
+                        .entry(relayer.to_string())
+                        .or_default()
+                        .insert(n, h.clone());
+                    h
+                }
+            };
+        }
+        parent
+    }
+}
+
+/// Validate the header and check the header is not contradictory with the headers submitted by
+/// the same relayer
+pub fn validate(
+    relayer: &str,
+    header: &Header,
+    submit_headers: &BTreeMap<usize, Vec<(Header, Vec<String>)>>,
+) -> bool {
+    let parent = header
+        .number
+        .checked_sub(1)
+        .and_then(|n| submit_headers.get(&n))
+        .and_then(|hs| {
+            hs.iter()
+                .map(|h| &h.0)
+                .find(|h| h.hash == header.parent_hash)
+        });
+    if !header.is_valid(parent) {
+        return false;
+    }
+    !submit_headers
+        .values()
+        .flatten()
+        .filter(|(_, relayers)| relayers.iter().any(|r| r == relayer))
+        .any(|(h, _)| header.contradicts(h))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn submit(
+        submit_headers: &mut BTreeMap<usize, Vec<(Header, Vec<String>)>>,
+        relayer: &str,
+        header: Header,
+    ) -> bool {
+        if validate(relayer, &header, submit_headers) {
+            submit_headers
+                .entry(header.number)
+                .or_default()
+                .push((header, vec![relayer.to_string()]));
+            true
+        } else {
+            false
+        }
+    }
+    #[test]
+    fn test_canonical_and_forged_chain() {
+        let mut chain: HeaderChain = HeaderConfig { D: 20480, T: 13 }.into();
+        let h = chain.header(10);
+        assert!(h.is_valid(Some(&chain.header(9))));
+        let f = chain.forge("Evil", 10, 5);
+        assert_ne!(chain.header(10).hash, f.hash);
+        assert!(f.is_valid(Some(&chain.forge("Evil", 9, 5))));
+        assert_eq!(chain.forge("Evil", 6, 5).parent_hash, chain.header(5).hash);
+        assert_eq!(chain.forge("Evil", 10, 5), f);
+    }
+    #[test]
+    fn test_parse_headers() {
+        let headers = parse_headers(
+            r#"[
+            {"jsonrpc": "2.0", "id": 1, "result": {"number": "0x65", "hash": "0xb2",
+            "parentHash": "0xb1", "difficulty": "0x5000", "timestamp": "0x20"}},
+            {"number": "0x64", "hash": "0xb1", "parentHash": "0xb0", "difficulty": "0x5000",
+            "timestamp": "0x10"}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(headers[0].number, 100);
+        assert_eq!(headers[1].timestamp, 32);
+        assert_eq!(headers[1].extra_data, "b2");
+        let mut chain: HeaderChain = headers.clone().into();
+        assert_eq!(chain.header(101), headers[1]);
+        assert!(chain.header(102).is_valid(Some(&headers[1])));
+
+        let error = parse_headers(
+            r#"[
+            {"number": "0x64", "hash": "0xb1", "parentHash": "0xb0", "difficulty": "0x5000",
+            "timestamp": "0x10"},
+            {"number": "0x65", "hash": "0xb2", "parentHash": "0xff", "difficulty": "0x5000",
+            "timestamp": "0x20"}
+            ]"#,
+        );
+        assert!(error.is_err());
+        assert!(format!("{}", parse_headers("[").unwrap_err()).contains("Ethereum headers"));
+    }
+    #[test]
+    fn test_validate_contradiction() {
+        let mut chain: HeaderChain = HeaderConfig { D: 20480, T: 13 }.into();
+        let mut submit_headers = BTreeMap::new();
+        assert!(submit(&mut submit_headers, "Evil", chain.forge("Evil", 100, 0)));
+        assert!(submit(&mut submit_headers, "Honest", chain.header(100)));
+        // branch is detected on the disputed block
+        assert_eq!(submit_headers[&100].len(), 2);
+
+        assert!(submit(&mut submit_headers, "Evil", chain.forge("Evil", 50, 0)));
+        assert!(submit(&mut submit_headers, "Evil", chain.header(48)));
+        // the honest header contradicts the forged one
+        assert!(!submit(&mut submit_headers, "Evil", chain.header(49)));
+        assert!(!submit(&mut submit_headers, "Evil", chain.header(50)));
+
+        let mut tampered = chain.header(25);
+        tampered.timestamp += 1;
+        assert!(!submit(&mut submit_headers, "Honest", tampered));
+    }
+}
@@ -0,0 +1,233 @@
+//! HTML module
+//! Render a simulation run as a self-contained HTML report, with the scenario parameters, the
+//! table of each round, the charts inlined as SVG, the relay positions and the final balances, so
+//! the results can be reviewed in a browser without any external asset.
+//!
+//! The charts are drawn with the `plot` feature.
+use serde_json::Value;
+
+use crate::chain::ChainsStatus;
+use crate::error::Error;
+use crate::game::GameResult;
+use crate::patch::join_key;
+use crate::scenario::ScenarioConfig;
+use crate::timeline::Timeline;
+
+/// The width of the relay positions in the report, which does not depend on the terminal
+static TIMELINE_WIDTH: usize = 100;
+
+static STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+table{border-collapse:collapse;margin-bottom:1em}\
+th,td{border:1px solid #ccc;padding:0.2em 0.6em;text-align:right}\
+th{background:#eee}td.text{text-align:left}\
+pre{background:#f6f6f6;padding:1em;overflow-x:auto}\
+svg{max-width:100%;height:auto;display:block;margin-bottom:1em}";
+
+/// Escape the text for HTML and SVG
+pub fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The values of the scenario as (path, value), the tables and lists are flatten
+fn parameters(path: String, value: &Value, output: &mut Vec<(String, String)>) {
+    match value {
+        Value::Object(table) => {
+            for (k, v) in table.iter() {
+                parameters(join_key(path.clone(), k), v, output);
+            }
+        }
+        Value::Array(list) => {
+            for (i, v) in list.iter().enumerate() {
+                parameters(format!("{}[{}]", path, i), v, output);
+            }
+        }
+        Value::String(s) => output.push((path, s.clone())),
+        v => output.push((path, v.to_string())),
+    }
+}
+
+fn row(cells: &[String]) -> String {
+    let mut output = "<tr>".to_string();
+    for c in cells {
+        output.push_str(&format!("<td>{}</td>", escape(c)));
+    }
+    output.push_str("</tr>\n");
+    output
+}
+
+fn head(cells: &[&str]) -> String {
+    let mut output = "<tr>".to_string();
+    for c in cells {
+        output.push_str(&format!("<th>{}</th>", c));
+    }
+    output.push_str("</tr>\n");
+    output
+}
+
+#[cfg(feature = "plot")]
+fn charts(result: &GameResult, chains_status: &ChainsStatus) -> Result<String, Error> {
+    let svgs = crate::plot::draw_svgs(result, chains_status)
+        .map_err(|e| Error::PlotError(format!("{:?}", e)))?;
+    Ok(svgs.concat())
+}
+
+#[cfg(not(feature = "plot"))]
+fn charts(_result: &GameResult, _chains_status: &ChainsStatus) -> Result<String, Error> {
+    Ok("<p>The charts are not drawn without the plot feature.</p>\n".to_string())
+}
+
+/// Render the game of the scenario as a HTML document
+pub fn report(
+    config: &ScenarioConfig,
+    result: &GameResult,
+    chains_status: &ChainsStatus,
+) -> Result<String, Error> {
+    let title = escape(config.title.as_deref().unwrap_or("Relayer Game"));
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>{}</style>\n</head>\n<body>\n<h1>{}</h1>\n",
+        title, STYLE, title
+    );
+
+    html.push_str("<h2>Scenario</h2>\n<table>\n");
+    html.push_str(&head(&["Parameter", "Value"]));
+    let mut rows = Vec::new();
+    if let Some(source) = config.source.as_ref() {
+        parameters(String::new(), source, &mut rows);
+    }
+    for (path, value) in rows {
+        html.push_str(&format!(
+            "<tr><td class=\"text\">{}</td><td class=\"text\">{}</td></tr>\n",
+            escape(&path),
+            escape(&value)
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Summary</h2>\n<table>\n");
+    let mut summary = vec![
+        ("Duration (blocks)", result.duration.to_string()),
+        ("Rounds", result.rounds.len().to_string()),
+        ("Max Bond Value", result.max_bond_value.to_string()),
+        (
+            "Latest Confirmed Ethereum Block",
+            result.latest_confirm_ethereum_block.to_string(),
+        ),
+    ];
+    if chains_status.block_producer.is_some() {
+        summary.push(("Elapsed Time (seconds)", result.elapsed_time.to_string()));
+    }
+    if chains_status.transaction_fee > 0.0 {
+        summary.push(("Transaction Fee", chains_status.transaction_fee.to_string()));
+    }
+    for (name, value) in summary {
+        html.push_str(&format!(
+            "<tr><th>{}</th><td>{}</td></tr>\n",
+            name,
+            escape(&value)
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Rounds</h2>\n<table>\n");
+    html.push_str(&head(&[
+        "Round",
+        "Bond",
+        "Challenge Time",
+        "Submitted Block",
+        "Next Target Block",
+        "Submissions",
+        "Missed",
+        "Rejected",
+        "Bond Pool",
+    ]));
+    for (i, r) in result.rounds.iter().enumerate() {
+        let submissions: Vec<String> = r
+            .submissions
+            .iter()
+            .map(|(id, lie)| format!("{}({})", id, if *lie { "lie" } else { "honest" }))
+            .collect();
+        html.push_str(&row(&[
+            (i + 1).to_string(),
+            r.bond.to_string(),
+            r.challenge_time.to_string(),
+            r.submit_ethereum_block.to_string(),
+            r.target_ethereum_block.to_string(),
+            submissions.join(" "),
+            r.missed.join(" "),
+            r.rejected.join(" "),
+            r.submit_bond_pool.to_string(),
+        ]));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str("<h2>Charts</h2>\n");
+    html.push_str(&charts(result, chains_status)?);
+
+    let timeline = Timeline::new(config, result, chains_status);
+    html.push_str(&format!(
+        "<h2>Relay Positions</h2>\n{}<pre>{}</pre>\n",
+        timeline.to_svg(),
+        escape(&timeline.render(TIMELINE_WIDTH))
+    ));
+    html.push_str(&format!(
+        "<h2>Final Balances</h2>\n<pre>{}</pre>\n",
+        escape(&chains_status.fmt_relayers_bar_chart(result.max_bond_value))
+    ));
+    html.push_str("</body>\n</html>\n");
+    Ok(html)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::run;
+    use std::str::FromStr;
+    #[test]
+    fn test_report() {
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			title = "Evil <&> Honest"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10"
+			reward_function = "split"
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LL"
+
+			[[relayers]]
+			name = "Honest"
+			choice = "HHH"
+			"#,
+        )
+        .unwrap();
+        let (chains_status, result) = run(&config).unwrap();
+        let html = report(&config, &result, &chains_status).unwrap();
+        assert!(html.contains("<title>Evil &lt;&amp;&gt; Honest</title>"));
+        assert!(
+            html.contains("<td class=\"text\">relayers[1].choice</td><td class=\"text\">HHH</td>")
+        );
+        assert!(html.contains("<td>Darwinia(honest) Evil(lie) Honest(honest)</td>"));
+        assert_eq!(html.matches("<tr><td>").count(), result.rounds.len());
+        let timeline = Timeline::new(&config, &result, &chains_status);
+        assert!(html.contains(&timeline.to_svg()));
+        assert!(html.contains(&escape(&timeline.render(TIMELINE_WIDTH))));
+        assert!(!html.contains("src="));
+    }
+}
@@ -6,234 +6,464 @@
 //! the reward distribution.
 //!
 
-use std::fs::File;
-use std::io::prelude::*;
-use std::str::FromStr;
-
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg};
 use colored::Colorize;
+use std::io::IsTerminal;
 
-use crate::sample::Equation as TargetEq;
-
+mod attack;
+mod block_time;
 mod bond;
 mod chain;
 mod challenge;
+mod check;
+mod compare;
+mod cost;
+mod equilibrium;
 mod error;
+mod game;
+#[cfg(test)]
+mod golden;
+mod header;
+mod html;
+mod optimise;
+mod patch;
 #[cfg(feature = "plot")]
 mod plot;
+#[cfg(test)]
+mod property;
 mod reward;
 mod sample;
 mod scenario;
+mod sensitivity;
+mod step;
+mod strategy;
+mod timeline;
+mod treasury;
 
-fn simulate_from_scenario(
+fn load_scenario(
     file_name: &str,
     patches: Vec<&str>,
-    debug: bool,
-) -> Result<(), error::Error> {
-    let mut file = File::open(file_name)?;
-    let mut contents = String::new();
-    file.read_to_string(&mut contents)?;
-    let mut config = <scenario::ScenarioConfig>::from_str(&contents)?;
+) -> Result<scenario::ScenarioConfig, error::Error> {
+    let mut config = scenario::ScenarioConfig::from_file(file_name)?;
     config.apply_patch(patches)?;
 
     if let Some(t) = &config.title {
         println!("{}", t.white());
     }
+    Ok(config)
+}
 
-    let mut iterator = config.get_iter();
-    let challenge_eq = config.get_challenge_equation()?;
-    let sample_eq = config.get_sample_equation()?;
-    let reward_eq = config.get_reward_equation()?;
-    let bond_eq = config.get_bond_equation()?;
-    let mut chains_status: chain::ChainsStatus = config.into();
-    let darwinia_start_block = chains_status.darwinia_block_hight;
-
-    // record variable pre submition
-    let mut challenge_times = Vec::<f64>::new();
-    let mut bonds = Vec::<f64>::new();
-
-    let mut reward_actions = Vec::<chain::Reward>::new();
-    let mut reward_from_previous_round = 0f64;
-    let mut rp = scenario::RelayPositions::default();
-    rp.relay_blocks
-        .push(vec![chains_status.submit_target_ethereum_block]);
+/// Write the charts of the game into the directory in the format
+#[cfg(feature = "plot")]
+fn draw_files(
+    dir: &str,
+    format: &str,
+    result: &game::GameResult,
+    chains_status: &chain::ChainsStatus,
+) -> Result<(), error::Error> {
+    let files = plot::draw_files(dir, format.parse()?, result, chains_status)
+        .map_err(|e| error::Error::PlotError(format!("{:?}", e)))?;
+    for f in files {
+        println!("Chart: {}", f.display());
+    }
+    Ok(())
+}
 
-    let mut latest_confirm_ethereum_block = 0;
+#[cfg(not(feature = "plot"))]
+fn draw_files(
+    _dir: &str,
+    _format: &str,
+    _result: &game::GameResult,
+    _chains_status: &chain::ChainsStatus,
+) -> Result<(), error::Error> {
+    Err(error::Error::CliError(
+        "the charts can not be written without the plot feature".to_string(),
+    ))
+}
 
-    while let Some(mut relayer_submissions) = iterator.next() {
-        let bond = bond_eq.calculate(iterator.submit_round);
-        bonds.push(bond);
-        let submition_times = chains_status.submitions.len();
-        let last_relayed_block = if submition_times > 0 {
-            chains_status.submitions[submition_times - 1]
-        } else {
-            (0, 0)
-        };
-        let challenge_time = if last_relayed_block.1 > chains_status.submit_target_ethereum_block {
-            challenge_eq.calculate(
-                chains_status.darwinia_block_hight - last_relayed_block.0,
-                last_relayed_block.1 - chains_status.submit_target_ethereum_block,
-            )
-        } else {
-            challenge_eq.calculate(
-                chains_status.darwinia_block_hight - last_relayed_block.0,
-                chains_status.submit_target_ethereum_block - last_relayed_block.1,
-            )
-        };
-        challenge_times.push(challenge_time as f64);
-
-        let total_lie_relayer = relayer_submissions.iter().filter(|r| r.1).count();
-        let mut r = reward_eq.calculate(
-            reward_from_previous_round,
-            total_lie_relayer as f64 * bond,
-            bond,
-            relayer_submissions
-                .iter()
-                .filter(|r| !r.1)
-                .map(|r| r.0.clone())
-                .collect(),
-        );
-        reward_from_previous_round = r.0;
-        reward_actions.append(&mut r.1);
-
-        if debug {
-            print!("{}", format!("{}", chains_status.fmt_status()).cyan());
-            println!("\tSubmission Plot: {}", rp.plot());
-            print!("\tSubmission(Bond: {}): ", bond);
-            for (r, lie) in relayer_submissions.iter() {
-                print!("{}", r);
-                if *lie {
-                    print!("(lie)");
-                } else {
-                    print!("(honest)");
-                }
-                print!(" ");
-            }
-            print!("\n");
+fn simulate_from_scenario(
+    file_name: &str,
+    patches: Vec<&str>,
+    debug: bool,
+    plot_dir: Option<(&str, &str)>,
+    html: Option<&str>,
+    timeline: bool,
+    timeline_svg: Option<&str>,
+) -> Result<(), error::Error> {
+    let config = load_scenario(file_name, patches)?;
+    if config.games.is_some() {
+        if plot_dir.is_some() || html.is_some() || timeline || timeline_svg.is_some() {
+            return Err(error::Error::ParameterError(
+                "the charts, report and timeline of concurrent games are not supported",
+            ));
         }
+        return simulate_concurrent(config, debug);
+    }
+    let has_treasury = config.treasury.is_some();
+    let mut chains_status: chain::ChainsStatus = config.clone().into();
+    let result = game::play(&config, &mut chains_status, debug)?;
 
-        let target_block = if 0 == total_lie_relayer {
-            latest_confirm_ethereum_block = chains_status.submit_target_ethereum_block;
-            sample_eq.calculate(
-                chains_status.submit_target_ethereum_block,
-                last_relayed_block.1,
-            )
-        } else {
-            sample_eq.calculate(
-                latest_confirm_ethereum_block,
-                chains_status.submit_target_ethereum_block,
-            )
-        };
-
-        let mut relay_blocks = Vec::new();
-        if chains_status.challengers.len() == 1 {
-            // relayer-challenger mod
-            for (challenger, _) in chains_status.challengers.clone().iter() {
-                chains_status.challenge_by(challenger.clone(), bond);
-                reward_actions.push(chain::Reward {
-                    from: chain::RewardFrom::Slash,
-                    to: challenger.clone(),
-                    value: bond * 2.0,
-                });
-            }
-        } else if chains_status.challengers.len() > 1 {
-            // relayer-challengers mod
-            let relayer = relayer_submissions[0].0.clone();
-            let mut is_additional_challenge = false;
-            for (challenger, obj) in chains_status.challengers.clone().iter() {
-                if obj.submit_round == submition_times + 1 {
-                    if is_additional_challenge {
-                        relayer_submissions.push((relayer.clone(), false));
-                        if total_lie_relayer == 0 {
-                            relay_blocks.push(
-                                chains_status.submit_target_ethereum_block * 2
-                                    - (chains_status.submit_target_ethereum_block
-                                        + last_relayed_block.1)
-                                        / 2,
-                            );
-                        } else {
-                            relay_blocks.push(
-                                (chains_status.submit_target_ethereum_block + last_relayed_block.1)
-                                    / 2,
-                            );
-                        }
-                    }
+    #[cfg(feature = "plot")]
+    plot::draw(
+        "Challenge Times",
+        result.rounds.len(),
+        result
+            .rounds
+            .iter()
+            .map(|r| r.challenge_time as f64)
+            .collect(),
+    )
+    .map_err(|e| error::Error::PlotError(format!("{:?}", e)))?;
 
-                    chains_status.challenge_by(challenger.clone(), bond);
-                    if obj.lie {
-                        // We can not sure the challenge is lie or not, so we return the bond
-                        reward_actions.push(chain::Reward {
-                            from: chain::RewardFrom::Slash,
-                            to: challenger.clone(),
-                            value: bond,
-                        });
-                    } else {
-                        reward_actions.push(chain::Reward {
-                            from: chain::RewardFrom::Slash,
-                            to: challenger.clone(),
-                            value: bond * 2.0,
-                        });
-                    }
+    #[cfg(feature = "plot")]
+    plot::draw(
+        "Bonds",
+        result.rounds.len(),
+        result.rounds.iter().map(|r| r.bond).collect(),
+    )
+    .map_err(|e| error::Error::PlotError(format!("{:?}", e)))?;
 
-                    is_additional_challenge = true;
-                }
-            }
+    if let Some((dir, format)) = plot_dir {
+        draw_files(dir, format, &result, &chains_status)?;
+    }
+    if let Some(file) = html {
+        std::fs::write(file, html::report(&config, &result, &chains_status)?)?;
+        println!("Report: {}", file);
+    }
+    if timeline || timeline_svg.is_some() {
+        let t = timeline::Timeline::new(&config, &result, &chains_status);
+        if timeline {
+            println!("{}", t.render(timeline::terminal_width()));
         }
+        if let Some(file) = timeline_svg {
+            std::fs::write(file, t.to_svg())?;
+            println!("Timeline: {}", file);
+        }
+    }
 
-        chains_status.submit(relayer_submissions, bond, challenge_time, target_block);
-
-        relay_blocks.push(chains_status.submit_target_ethereum_block);
-        rp.relay_blocks.push(relay_blocks);
-
-        // TODO: make this as an option
-        chains_status.should_balance();
-
-        if debug {
-            println!(
-                "\tNext Etherem Target Block: {}",
-                chains_status.submit_target_ethereum_block
-            );
-            println!("\tChallenge Time: {} blocks", challenge_time);
-            println!("\tRelayer Status: {}", chains_status.fmt_relayers_status());
+    println!(
+        "Final {}\n{}",
+        chains_status,
+        chains_status.fmt_relayers_bar_chart(result.max_bond_value)
+    );
+    println!(
+        "Duration: {} blocks,  Max Bond Value: {}",
+        result.duration, result.max_bond_value
+    );
+    for (i, r) in result.rounds.iter().enumerate() {
+        if !r.missed.is_empty() {
             println!(
-                "\tChallenger Status: {}",
-                chains_status.fmt_challengers_status()
+                "Missed Challenge Window: {} (round {})",
+                r.missed.join(", "),
+                i + 1
             );
+        }
+        if !r.rejected.is_empty() {
             println!(
-                "\tSubmit Bond Pool Status: {}",
-                chains_status.submit_bond_pool
+                "Rejected Header: {} (round {})",
+                r.rejected.join(", "),
+                i + 1
             );
         }
     }
-    let max_bond_value = chains_status.submit_bond_pool;
-    chains_status.reward(reward_actions);
+    if chains_status.block_producer.is_some() {
+        println!("Elapsed Time: {} seconds", result.elapsed_time);
+    }
+    if has_treasury || chains_status.treasury.paid > 0.0 {
+        println!("{}", chains_status.treasury);
+    }
+    if chains_status.transaction_fee > 0.0 {
+        println!("Transaction Fee: {}", chains_status.transaction_fee);
+    }
 
-    #[cfg(feature = "plot")]
-    plot::draw("Challenge Times", iterator.submit_round, challenge_times)
-        .map_err(|e| error::Error::PlotError(format!("{:?}", e)))?;
+    Ok(())
+}
 
-    #[cfg(feature = "plot")]
-    plot::draw("Bonds", iterator.submit_round, bonds)
-        .map_err(|e| error::Error::PlotError(format!("{:?}", e)))?;
+/// Play the games on different disputed blocks concurrently, and show the bond locked by each
+/// participator in all the games at the same time
+fn simulate_concurrent(config: scenario::ScenarioConfig, debug: bool) -> Result<(), error::Error> {
+    let has_treasury = config.treasury.is_some();
+    let mut configs = vec![config.clone()];
+    configs.append(&mut config.get_concurrent_scenarios()?);
+    let mut chains_status: chain::ChainsStatus = config.into();
+    let result = game::play_concurrent(&configs, &mut chains_status, debug)?;
 
+    let mut max_bond_value = 0f64;
+    for (disputed_block, r) in result.games.iter() {
+        max_bond_value = max_bond_value.max(r.max_bond_value);
+        println!(
+            "Game on Ethereum #{}: Duration: {} blocks, Rounds: {}, Max Bond Value: {}",
+            disputed_block,
+            r.duration,
+            r.rounds.len(),
+            r.max_bond_value
+        );
+    }
     println!(
         "Final {}\n{}",
         chains_status,
         chains_status.fmt_relayers_bar_chart(max_bond_value)
     );
+    println!("Duration: {} blocks", result.duration);
+    print!("Max Locked Bond: ");
+    for (name, bond) in result.max_locked_bonds.iter() {
+        print!("{}: {} ", name, bond);
+    }
+    println!();
+    if has_treasury || chains_status.treasury.paid > 0.0 {
+        println!("{}", chains_status.treasury);
+    }
+    if chains_status.transaction_fee > 0.0 {
+        println!("Transaction Fee: {}", chains_status.transaction_fee);
+    }
+
+    Ok(())
+}
+
+/// Parse the number of games played one after another, at least one game is played
+fn parse_games(games: &str) -> Result<usize, error::Error> {
+    match games.parse::<usize>() {
+        Ok(g) if g > 0 => Ok(g),
+        _ => Err(error::Error::CliError(
+            "games should be a positive number".to_string(),
+        )),
+    }
+}
+
+/// The Ethereum blocks confirmed per Darwinia block, zero if no Darwinia block is produced
+fn throughput(confirmed_blocks: usize, darwinia_blocks: usize) -> f64 {
+    if darwinia_blocks == 0 {
+        0.0
+    } else {
+        confirmed_blocks as f64 / darwinia_blocks as f64
+    }
+}
+
+/// Play the games of the scenario one after another, the next game starts from the latest
+/// confirmed Ethereum block of the previous game, and the balances are carried across the games.
+fn simulate_long_run(
+    file_name: &str,
+    patches: Vec<&str>,
+    debug: bool,
+    games: usize,
+) -> Result<(), error::Error> {
+    let config = load_scenario(file_name, patches)?;
+    if config.games.is_some() {
+        return Err(error::Error::CliError(
+            "concurrent games can not play in long-run mode".to_string(),
+        ));
+    }
+    let sample_eq = config.get_sample_equation()?;
+    let has_treasury = config.treasury.is_some();
+    let mut chains_status: chain::ChainsStatus = config.clone().into();
+    let darwinia_start_block = chains_status.darwinia_block_hight;
+    let ethereum_start_block = chains_status.genesis.1;
+    let mut latest_confirm = ethereum_start_block;
+    let mut max_bond_value = 0f64;
+
+    for g in 0..games {
+        if g > 0 {
+            chains_status.next_game(&sample_eq, latest_confirm);
+        }
+        let result = game::play(&config, &mut chains_status, debug)?;
+        latest_confirm = result.latest_confirm_ethereum_block;
+        max_bond_value = max_bond_value.max(result.max_bond_value);
+        println!(
+            "Game #{}: Duration: {} blocks, Confirmed Ethereum: #{} (+{}), Max Bond Value: {}",
+            g + 1,
+            result.duration,
+            result.latest_confirm_ethereum_block,
+            result.confirmed_blocks,
+            result.max_bond_value
+        );
+    }
+
+    let darwinia_blocks = chains_status.darwinia_block_hight - darwinia_start_block;
+    let confirmed_blocks = latest_confirm.saturating_sub(ethereum_start_block);
+    println!("Final {}", chains_status);
+    println!(
+        "Games: {}, Duration: {} blocks, Confirmed Ethereum Blocks: {}, Max Bond Value: {}",
+        games, darwinia_blocks, confirmed_blocks, max_bond_value
+    );
     println!(
-        "Duration: {} blocks,  Max Bond Value: {}",
-        chains_status.darwinia_block_hight - darwinia_start_block,
-        max_bond_value
+        "Throughput: {} Ethereum blocks / Darwinia block",
+        throughput(confirmed_blocks, darwinia_blocks)
     );
+    if chains_status.block_producer.is_some() {
+        println!("Elapsed Time: {} seconds", chains_status.elapsed_time);
+    }
+    if has_treasury || chains_status.treasury.paid > 0.0 {
+        println!("{}", chains_status.treasury);
+    }
+    if chains_status.transaction_fee > 0.0 {
+        println!("Transaction Fee: {}", chains_status.transaction_fee);
+    }
 
     Ok(())
 }
 
+/// Search the choices of the attacker, and show the attack delaying the game most per unit of
+/// slashed bond and the profitable attacks
+fn analyse_attack(
+    file_name: &str,
+    patches: Vec<&str>,
+    attacker: Option<&str>,
+    length: &str,
+) -> Result<(), error::Error> {
+    let length = length
+        .parse::<usize>()
+        .map_err(|_| error::Error::CliError("length should be a number".to_string()))?;
+    let config = load_scenario(file_name, patches)?;
+    println!("{}", attack::analyse(&config, attacker, length)?);
+    Ok(())
+}
+
+/// Simulate the payoff matrix of the participants, and show the pure-strategy Nash equilibria and
+/// the best responses to the honest participants
+fn solve_equilibria(
+    file_name: &str,
+    patches: Vec<&str>,
+    length: &str,
+    matrix: bool,
+) -> Result<(), error::Error> {
+    let length = length
+        .parse::<usize>()
+        .map_err(|_| error::Error::CliError("length should be a number".to_string()))?;
+    let config = load_scenario(file_name, patches)?;
+    let equilibria = equilibrium::solve(&config, length)?;
+    if matrix {
+        print!("{}", equilibria.fmt_matrix());
+    }
+    println!("{}", equilibria);
+    Ok(())
+}
+
+/// Search the parameters in their ranges for the best objective subject to the constraints, and
+/// show the best parameters as patches with the metrics simulated
+fn optimise_parameters(
+    file_name: &str,
+    patches: Vec<&str>,
+    parameters: Vec<&str>,
+    objective: &str,
+    constraints: Vec<&str>,
+    method: &str,
+    evaluations: &str,
+) -> Result<(), error::Error> {
+    let parameters = parameters
+        .into_iter()
+        .map(str::parse)
+        .collect::<Result<Vec<optimise::Parameter>, _>>()?;
+    let constraints = constraints
+        .into_iter()
+        .map(str::parse)
+        .collect::<Result<Vec<optimise::Constraint>, _>>()?;
+    let evaluations = evaluations
+        .parse::<usize>()
+        .map_err(|_| error::Error::CliError("evaluations should be a number".to_string()))?;
+    let config = load_scenario(file_name, patches)?;
+    println!(
+        "{}",
+        optimise::optimise(
+            &config,
+            parameters,
+            objective.parse()?,
+            constraints,
+            method.parse()?,
+            evaluations,
+        )?
+    );
+    Ok(())
+}
+
+/// Perturb each numeric parameter, and show the tornado tables of the duration, the max bond pool
+/// and the balance of each participant
+fn analyse_sensitivity(
+    file_name: &str,
+    patches: Vec<&str>,
+    delta: &str,
+) -> Result<(), error::Error> {
+    let delta = delta
+        .parse::<f64>()
+        .map_err(|_| error::Error::CliError("delta should be a number".to_string()))?;
+    let config = load_scenario(file_name, patches)?;
+    println!("{}", sensitivity::analyse(&config, delta)?);
+    Ok(())
+}
+
+/// Play the game of the scenario round by round with the commands from the standard input
+fn step_scenario(file_name: &str, patches: Vec<&str>) -> Result<(), error::Error> {
+    let config = load_scenario(file_name, patches)?;
+    let stdin = std::io::stdin();
+    let redraw = std::io::stdout().is_terminal();
+    step::interact(config, stdin.lock(), std::io::stdout(), redraw)
+}
+
+/// Play two scenarios, or one scenario with two patch sets, and show the differences in text or
+/// JSON, the title is not printed such that the JSON can be piped
+fn compare_scenarios(
+    file_names: Vec<&str>,
+    patches_a: Vec<&str>,
+    patches_b: Vec<&str>,
+    json: bool,
+) -> Result<(), error::Error> {
+    let (file_a, file_b) = match file_names.as_slice() {
+        [a] => (*a, *a),
+        [a, b] => (*a, *b),
+        _ => {
+            return Err(error::Error::CliError(
+                "compare one scenario with two patch sets or two scenarios".to_string(),
+            ))
+        }
+    };
+    let load = |file_name: &str, patches: Vec<&str>| {
+        let label = patches
+            .iter()
+            .fold(file_name.to_string(), |l, p| format!("{} -p {}", l, p));
+        let mut config = scenario::ScenarioConfig::from_file(file_name)?;
+        config.apply_patch(patches)?;
+        Ok::<_, error::Error>((label, config))
+    };
+    let (label_a, a) = load(file_a, patches_a)?;
+    let (label_b, b) = load(file_b, patches_b)?;
+    let comparison = compare::compare((&label_a, &a), (&label_b, &b))?;
+    if json {
+        println!("{}", comparison.to_json()?);
+    } else {
+        println!("{}", comparison);
+    }
+    Ok(())
+}
+
+/// Check the scenario files, print the problems with the file name, field path and location,
+/// return false if there is any problem
+fn check_scenarios(file_names: Vec<&str>) -> bool {
+    let mut passed = true;
+    for f in file_names {
+        match check::check_file(f) {
+            Ok(problems) if problems.is_empty() => println!("{}: {}", f, "ok".green()),
+            Ok(problems) => {
+                passed = false;
+                for p in problems {
+                    if p.location.is_some() {
+                        println!("{}:{}", f, p);
+                    } else {
+                        println!("{}: {}", f, p);
+                    }
+                }
+            }
+            Err(e) => {
+                passed = false;
+                println!("{}: {}", f, e);
+            }
+        }
+    }
+    passed
+}
+
 fn main() {
     let matches = App::new("Relayer Game")
         .about("Relayer Gaming Simulation Tool")
-        .arg("<scenario> 'scenario yaml file'")
+        .arg("<scenario> 'scenario file in TOML, YAML or JSON'")
         .arg("-v, --verbose 'show the detail of each submit'")
         .arg(
             Arg::with_name("patch")
@@ -241,13 +471,241 @@ fn main() {
                 .short('p')
                 .takes_value(true),
         )
+        .arg("-g, --games=[games] 'play the games one after another over a long chain history'")
+        .arg("--plot-dir=[plot-dir] 'write the charts of the game into the directory'")
+        .arg("--plot-format=[plot-format] 'the format of the charts, svg or png, default svg'")
+        .arg("--html=[html] 'write the report of the game into the HTML file'")
+        .arg("-t, --timeline 'show the timeline of the relay positions and the result of each participant'")
+        .arg("--timeline-svg=[timeline-svg] 'write the timeline into the SVG file'")
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            App::new("check")
+                .about("Check the scenario files and report all the problems")
+                .arg("<scenario>... 'scenario files to check'"),
+        )
+        .subcommand(
+            App::new("attack")
+                .about("Search the choices of the attacker for the most delay per slashed bond and the profit")
+                .arg("<scenario> 'scenario file in TOML, YAML or JSON'")
+                .arg(
+                    Arg::with_name("patch")
+                        .multiple(true)
+                        .short('p')
+                        .takes_value(true),
+                )
+                .arg("-a, --attacker=[attacker] 'the id of the attacker, default the first relayer or challenger'")
+                .arg("-l, --length=[length] 'the max length of the choices searched, default 4'"),
+        )
+        .subcommand(
+            App::new("equilibrium")
+                .about("Simulate the payoff matrix of the participants and find the pure-strategy Nash equilibria")
+                .arg("<scenario> 'scenario file in TOML, YAML or JSON'")
+                .arg(
+                    Arg::with_name("patch")
+                        .multiple(true)
+                        .short('p')
+                        .takes_value(true),
+                )
+                .arg("-l, --length=[length] 'the length of the choices, default 2'")
+                .arg("-m, --matrix 'show the payoff of each strategy profile'"),
+        )
+        .subcommand(
+            App::new("optimise")
+                .about("Search the parameters for the best objective subject to the constraints")
+                .arg("<scenario> 'scenario file in TOML, YAML or JSON'")
+                .arg(
+                    Arg::with_name("patch")
+                        .multiple(true)
+                        .short('p')
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("parameter")
+                        .about("the field and the range to search, as path=min..max")
+                        .multiple(true)
+                        .required(true)
+                        .short('x')
+                        .long("parameter")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("constraint")
+                        .about("the bound of the metric, as metric>=bound or metric<=bound")
+                        .multiple(true)
+                        .short('c')
+                        .long("constraint")
+                        .takes_value(true),
+                )
+                .arg("-o, --objective=[objective] 'the metric to minimise or maximise, as min:metric or max:metric, default min:duration'")
+                .arg("-m, --method=[method] 'grid, random or nelder-mead, default grid'")
+                .arg("-n, --evaluations=[evaluations] 'the max games to simulate, default 100'"),
+        )
+        .subcommand(
+            App::new("sensitivity")
+                .about("Perturb each numeric parameter and rank the parameters by the effect")
+                .arg("<scenario> 'scenario file in TOML, YAML or JSON'")
+                .arg(
+                    Arg::with_name("patch")
+                        .multiple(true)
+                        .short('p')
+                        .takes_value(true),
+                )
+                .arg("-d, --delta=[delta] 'the relative perturbation of each parameter, default 0.1'"),
+        )
+        .subcommand(
+            App::new("compare")
+                .about("Play two scenarios, or one scenario with two patch sets, and show the differences")
+                .arg("<scenario>... 'one or two scenario files in TOML, YAML or JSON'")
+                .arg(
+                    Arg::with_name("patch-a")
+                        .about("the patch of the first game")
+                        .multiple(true)
+                        .short('a')
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("patch-b")
+                        .about("the patch of the second game")
+                        .multiple(true)
+                        .short('b')
+                        .takes_value(true),
+                )
+                .arg("-j, --json 'show the differences in JSON'"),
+        )
+        .subcommand(
+            App::new("step")
+                .about("Play the game round by round, change the moves and step back interactively")
+                .arg("<scenario> 'scenario file in TOML, YAML or JSON'")
+                .arg(
+                    Arg::with_name("patch")
+                        .multiple(true)
+                        .short('p')
+                        .takes_value(true),
+                ),
+        )
         .get_matches();
-    match simulate_from_scenario(
-        matches.value_of("scenario").unwrap(),
-        matches.values_of("patch").unwrap_or_default().collect(),
-        matches.is_present("verbose"),
-    ) {
+    if let Some(matches) = matches.subcommand_matches("check") {
+        if !check_scenarios(matches.values_of("scenario").unwrap().collect()) {
+            std::process::exit(1);
+        }
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("attack") {
+        if let Err(e) = analyse_attack(
+            matches.value_of("scenario").unwrap(),
+            matches.values_of("patch").unwrap_or_default().collect(),
+            matches.value_of("attacker"),
+            matches.value_of("length").unwrap_or("4"),
+        ) {
+            println!("{}", e);
+        }
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("equilibrium") {
+        if let Err(e) = solve_equilibria(
+            matches.value_of("scenario").unwrap(),
+            matches.values_of("patch").unwrap_or_default().collect(),
+            matches.value_of("length").unwrap_or("2"),
+            matches.is_present("matrix"),
+        ) {
+            println!("{}", e);
+        }
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("optimise") {
+        if let Err(e) = optimise_parameters(
+            matches.value_of("scenario").unwrap(),
+            matches.values_of("patch").unwrap_or_default().collect(),
+            matches.values_of("parameter").unwrap_or_default().collect(),
+            matches.value_of("objective").unwrap_or("min:duration"),
+            matches.values_of("constraint").unwrap_or_default().collect(),
+            matches.value_of("method").unwrap_or("grid"),
+            matches.value_of("evaluations").unwrap_or("100"),
+        ) {
+            println!("{}", e);
+        }
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("sensitivity") {
+        if let Err(e) = analyse_sensitivity(
+            matches.value_of("scenario").unwrap(),
+            matches.values_of("patch").unwrap_or_default().collect(),
+            matches.value_of("delta").unwrap_or("0.1"),
+        ) {
+            println!("{}", e);
+        }
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("compare") {
+        if let Err(e) = compare_scenarios(
+            matches.values_of("scenario").unwrap().collect(),
+            matches.values_of("patch-a").unwrap_or_default().collect(),
+            matches.values_of("patch-b").unwrap_or_default().collect(),
+            matches.is_present("json"),
+        ) {
+            println!("{}", e);
+        }
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("step") {
+        if let Err(e) = step_scenario(
+            matches.value_of("scenario").unwrap(),
+            matches.values_of("patch").unwrap_or_default().collect(),
+        ) {
+            println!("{}", e);
+        }
+        return;
+    }
+    let result = if let Some(games) = matches.value_of("games") {
+        let unsupported = [
+            "plot-dir",
+            "plot-format",
+            "html",
+            "timeline",
+            "timeline-svg",
+        ];
+        if unsupported.iter().any(|o| matches.is_present(o)) {
+            Err(error::Error::ParameterError(
+                "the charts, report and timeline of the games one after another are not supported",
+            ))
+        } else {
+            parse_games(games).and_then(|games| {
+                simulate_long_run(
+                    matches.value_of("scenario").unwrap(),
+                    matches.values_of("patch").unwrap_or_default().collect(),
+                    matches.is_present("verbose"),
+                    games,
+                )
+            })
+        }
+    } else {
+        simulate_from_scenario(
+            matches.value_of("scenario").unwrap(),
+            matches.values_of("patch").unwrap_or_default().collect(),
+            matches.is_present("verbose"),
+            matches
+                .value_of("plot-dir")
+                .map(|d| (d, matches.value_of("plot-format").unwrap_or("svg"))),
+            matches.value_of("html"),
+            matches.is_present("timeline"),
+            matches.value_of("timeline-svg"),
+        )
+    };
+    match result {
         Err(e) => println!("{}", e),
         _ => {}
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_games_and_throughput() {
+        assert_eq!(parse_games("3").unwrap(), 3);
+        assert!(parse_games("0").is_err());
+        assert!(parse_games("x").is_err());
+        assert_eq!(throughput(10, 0), 0.0);
+        assert_eq!(throughput(10, 20), 0.5);
+    }
+}
@@ -0,0 +1,800 @@
+//! Optimise module
+//! Search the parameters of the scenario in their ranges for the best objective subject to the
+//! constraints, for example minimise the duration subject to `attacker_loss>=100` and
+//! `honest_profit>=0`.  Each parameter set is applied as the patches on the scenario, and the game
+//! is played again to measure the metrics.
+//!
+//! The methods are
+//! - `grid`: evaluate the grid of the ranges, and refine the grid around the best one
+//! - `random`: evaluate the random points in the ranges from the `seed` of the scenario
+//! - `nelder-mead`: the Nelder–Mead simplex method in the ranges
+//!
+//! The parameter set violating less is better, and the objective decides between the sets
+//! satisfying all the constraints.
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::error::Error;
+use crate::game::run;
+use crate::scenario::ScenarioConfig;
+
+/// The points of each parameter in a grid
+static GRID_POINTS: usize = 5;
+
+/// The simplex smaller than this in the normalized ranges is deemed as converged
+static TOLERANCE: f64 = 0.000_001;
+
+/// # Metrics
+/// The metrics of the game simulated, the attackers are the relayers submitting lies in the game,
+/// and the others except Darwinia are honest
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Metrics {
+    /// The Darwinia blocks of the game
+    pub duration: f64,
+    /// The max value of the bond pool
+    pub max_bond: f64,
+    /// The balance lost by all the attackers
+    pub attacker_loss: f64,
+    /// The least balance earned by the honest participants
+    pub honest_profit: f64,
+    /// The transaction fee paid by all the participants
+    pub fee: f64,
+}
+
+impl Metrics {
+    pub const NAMES: [&'static str; 5] = [
+        "duration",
+        "max_bond",
+        "attacker_loss",
+        "honest_profit",
+        "fee",
+    ];
+    /// Play the game of the scenario and measure the metrics
+    pub fn measure(config: &ScenarioConfig) -> Result<Self, Error> {
+        if config.games.is_some() {
+            return Err(Error::ParameterError(
+                "Concurrent games can not be optimised",
+            ));
+        }
+        let (chains_status, result) = run(config)?;
+        let mut metrics = Metrics {
+            duration: result.duration as f64,
+            max_bond: result.max_bond_value,
+            fee: chains_status.transaction_fee,
+            ..Default::default()
+        };
+        let lied = |id: &str| {
+            result
+                .rounds
+                .iter()
+                .flat_map(|r| r.submissions.iter())
+                .any(|(r, lie)| r == id && *lie)
+        };
+        let mut honest = Vec::new();
+        for r in config.relayers.iter().filter(|r| r.id() != "Darwinia") {
+            let s = &chains_status.relayers[r.id()];
+            let earned = s.balance() - s.initial_balance;
+            if lied(r.id()) {
+                metrics.attacker_loss -= earned;
+            } else {
+                honest.push(earned);
+            }
+        }
+        for c in config.challengers.iter().flatten() {
+            let s = &chains_status.challengers[c.id()];
+            honest.push(s.balance() - s.initial_balance);
+        }
+        metrics.honest_profit = honest
+            .into_iter()
+            .fold(None, |m: Option<f64>, e| Some(m.map_or(e, |m| m.min(e))))
+            .unwrap_or_default();
+        Ok(metrics)
+    }
+    /// The metric of the name
+    pub fn get(&self, name: &str) -> Option<f64> {
+        match name {
+            "duration" => Some(self.duration),
+            "max_bond" => Some(self.max_bond),
+            "attacker_loss" => Some(self.attacker_loss),
+            "honest_profit" => Some(self.honest_profit),
+            "fee" => Some(self.fee),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Duration: {} blocks, Max Bond Value: {}, Attacker Loss: {}, Honest Profit: {}, Transaction Fee: {}",
+            self.duration, self.max_bond, self.attacker_loss, self.honest_profit, self.fee
+        )
+    }
+}
+
+fn metric_name(name: &str) -> Result<String, Error> {
+    let name = name.trim();
+    if Metrics::NAMES.contains(&name) {
+        Ok(name.to_string())
+    } else {
+        Err(Error::CliError(format!(
+            "{}: unknown metric, the metrics are {}",
+            name,
+            Metrics::NAMES.join(", ")
+        )))
+    }
+}
+
+/// # Parameter
+/// The field of the scenario and the range to search, as `path=min..max`, for example
+/// `challenge_linear.C=1..20`.  The parameter is an integer if both bounds are integers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub path: String,
+    pub min: f64,
+    pub max: f64,
+    pub integer: bool,
+}
+
+impl Parameter {
+    /// The value at the position in the normalized range [0, 1]
+    fn value(&self, x: f64) -> f64 {
+        let v = self.min + (self.max - self.min) * x.clamp(0.0, 1.0);
+        if self.integer {
+            v.round()
+        } else {
+            v
+        }
+    }
+    /// The patch of the value
+    fn patch(&self, v: f64) -> String {
+        format!("{}={}", self.path, v)
+    }
+}
+
+impl FromStr for Parameter {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::PatchParameterError(format!("{}: should be path=min..max", s));
+        let mut kv = s.splitn(2, '=');
+        let (path, range) = match (kv.next(), kv.next()) {
+            (Some(k), Some(v)) if !k.trim().is_empty() => (k.trim(), v.trim()),
+            _ => return Err(invalid()),
+        };
+        let mut bounds = range.splitn(2, "..");
+        let (min, max) = match (bounds.next(), bounds.next()) {
+            (Some(min), Some(max)) => (min.trim(), max.trim()),
+            _ => return Err(invalid()),
+        };
+        let integer = min.parse::<i64>().is_ok() && max.parse::<i64>().is_ok();
+        let (min, max) = (
+            min.parse::<f64>().map_err(|_| invalid())?,
+            max.parse::<f64>().map_err(|_| invalid())?,
+        );
+        if min > max {
+            return Err(Error::PatchParameterError(format!(
+                "{}: the min should not be more than the max",
+                s
+            )));
+        }
+        Ok(Parameter {
+            path: path.to_string(),
+            min,
+            max,
+            integer,
+        })
+    }
+}
+
+/// # Objective
+/// The metric to minimise or maximise, as `min:duration` or `max:attacker_loss`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Objective {
+    pub metric: String,
+    pub maximise: bool,
+}
+
+impl Objective {
+    /// The value to minimise
+    fn cost(&self, metrics: &Metrics) -> f64 {
+        let v = metrics.get(&self.metric).unwrap_or_default();
+        if self.maximise {
+            -v
+        } else {
+            v
+        }
+    }
+}
+
+impl FromStr for Objective {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut kv = s.splitn(2, ':');
+        let maximise = match kv.next().map(str::trim) {
+            Some("min") => false,
+            Some("max") => true,
+            _ => {
+                return Err(Error::CliError(format!(
+                    "{}: should be min:metric or max:metric",
+                    s
+                )))
+            }
+        };
+        Ok(Objective {
+            metric: metric_name(kv.next().unwrap_or_default())?,
+            maximise,
+        })
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.maximise {
+            write!(f, "maximise {}", self.metric)
+        } else {
+            write!(f, "minimise {}", self.metric)
+        }
+    }
+}
+
+/// # Constraint
+/// The bound of the metric, as `attacker_loss>=100` or `duration<=3000`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Constraint {
+    pub metric: String,
+    /// the metric should be no less than the bound, otherwise no more than the bound
+    pub at_least: bool,
+    pub bound: f64,
+}
+
+impl Constraint {
+    /// How much the metrics violate the constraint, 0 if satisfied
+    fn violation(&self, metrics: &Metrics) -> f64 {
+        let v = metrics.get(&self.metric).unwrap_or_default();
+        if self.at_least {
+            (self.bound - v).max(0.0)
+        } else {
+            (v - self.bound).max(0.0)
+        }
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (i, at_least) = match (s.find(">="), s.find("<=")) {
+            (Some(i), None) => (i, true),
+            (None, Some(i)) => (i, false),
+            _ => {
+                return Err(Error::CliError(format!(
+                    "{}: should be metric>=bound or metric<=bound",
+                    s
+                )))
+            }
+        };
+        Ok(Constraint {
+            metric: metric_name(&s[..i])?,
+            at_least,
+            bound: s[i + 2..]
+                .trim()
+                .parse::<f64>()
+                .map_err(|_| Error::CliError(format!("{}: the bound should be a number", s)))?,
+        })
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let op = if self.at_least { ">=" } else { "<=" };
+        write!(f, "{}{}{}", self.metric, op, self.bound)
+    }
+}
+
+/// # Method
+/// The method to search the parameters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    Grid,
+    Random,
+    NelderMead,
+}
+
+impl FromStr for Method {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grid" => Ok(Method::Grid),
+            "random" => Ok(Method::Random),
+            "nelder-mead" => Ok(Method::NelderMead),
+            _ => Err(Error::CliError(format!(
+                "{}: the method should be grid, random or nelder-mead",
+                s
+            ))),
+        }
+    }
+}
+
+/// # Candidate
+/// The parameter set evaluated
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// The values of the parameters
+    pub values: Vec<f64>,
+    pub metrics: Metrics,
+    /// The sum of the violations of the constraints, infinite if the scenario is not valid
+    pub violation: f64,
+    /// The value of the objective to minimise
+    pub cost: f64,
+}
+
+impl Candidate {
+    pub fn feasible(&self) -> bool {
+        self.violation == 0.0
+    }
+    fn compare(&self, other: &Candidate) -> Ordering {
+        (self.violation, self.cost)
+            .partial_cmp(&(other.violation, other.cost))
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// # Optimum
+/// The best parameter set found
+#[derive(Debug)]
+pub struct Optimum {
+    pub parameters: Vec<Parameter>,
+    pub objective: Objective,
+    pub constraints: Vec<Constraint>,
+    pub best: Candidate,
+    /// The games simulated
+    pub evaluations: usize,
+}
+
+impl Optimum {
+    /// The patches of the best parameter set
+    pub fn patches(&self) -> Vec<String> {
+        self.parameters
+            .iter()
+            .zip(self.best.values.iter())
+            .map(|(p, v)| p.patch(*v))
+            .collect()
+    }
+}
+
+impl fmt::Display for Optimum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Objective: {}", self.objective)?;
+        if !self.constraints.is_empty() {
+            write!(
+                f,
+                " subject to {}",
+                self.constraints
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )?;
+        }
+        writeln!(f, "\nEvaluations: {}", self.evaluations)?;
+        if self.best.feasible() {
+            writeln!(f, "Best Parameters:")?;
+        } else {
+            writeln!(
+                f,
+                "Best Parameters (violating the constraints by {}):",
+                self.best.violation
+            )?;
+        }
+        for p in self.patches() {
+            writeln!(f, "\t-p {}", p)?;
+        }
+        write!(f, "{}", self.best.metrics)
+    }
+}
+
+/// # Optimiser
+/// The evaluations of the parameter sets in the normalized ranges
+struct Optimiser<'a> {
+    config: &'a ScenarioConfig,
+    parameters: &'a [Parameter],
+    objective: &'a Objective,
+    constraints: &'a [Constraint],
+    budget: usize,
+    evaluations: usize,
+    /// The parameter sets evaluated, the same set is not simulated again
+    evaluated: Vec<Candidate>,
+    best: Option<Candidate>,
+    error: Option<Error>,
+}
+
+impl<'a> Optimiser<'a> {
+    fn exhausted(&self) -> bool {
+        self.evaluations >= self.budget
+    }
+    /// Evaluate the parameter set at the position in the normalized ranges
+    fn evaluate(&mut self, x: &[f64]) -> Candidate {
+        let values: Vec<f64> = self
+            .parameters
+            .iter()
+            .zip(x.iter())
+            .map(|(p, x)| p.value(*x))
+            .collect();
+        if let Some(c) = self.evaluated.iter().find(|c| c.values == values) {
+            return c.clone();
+        }
+        let patches: Vec<String> = self
+            .parameters
+            .iter()
+            .zip(values.iter())
+            .map(|(p, v)| p.patch(*v))
+            .collect();
+        self.evaluations += 1;
+        let mut config = self.config.clone();
+        let candidate = match config
+            .apply_patch(patches.iter().map(String::as_str).collect())
+            .and_then(|_| Metrics::measure(&config))
+        {
+            Ok(metrics) => Candidate {
+                violation: self.constraints.iter().map(|c| c.violation(&metrics)).sum(),
+                cost: self.objective.cost(&metrics),
+                values,
+                metrics,
+            },
+            Err(e) => {
+                self.error.get_or_insert(e);
+                Candidate {
+                    values,
+                    metrics: Metrics::default(),
+                    violation: f64::INFINITY,
+                    cost: f64::INFINITY,
+                }
+            }
+        };
+        if candidate.cost.is_finite()
+            && self
+                .best
+                .as_ref()
+                .filter(|b| candidate.compare(b) != Ordering::Less)
+                .is_none()
+        {
+            self.best = Some(candidate.clone());
+        }
+        self.evaluated.push(candidate.clone());
+        candidate
+    }
+    /// Evaluate the grids, and shrink the ranges by half around the best point after each grid
+    fn grid(&mut self) {
+        let dimensions = self.parameters.len() as u32;
+        let mut points = GRID_POINTS;
+        while points > 2 && points.pow(dimensions) > self.budget {
+            points -= 1;
+        }
+        let mut ranges = vec![(0.0, 1.0); self.parameters.len()];
+        while !self.exhausted() {
+            let evaluations = self.evaluations;
+            for i in 0..points.pow(dimensions) {
+                if self.exhausted() {
+                    break;
+                }
+                let mut index = i;
+                let x: Vec<f64> = ranges
+                    .iter()
+                    .map(|(low, high)| {
+                        let k = index % points;
+                        index /= points;
+                        low + (high - low) * k as f64 / (points - 1) as f64
+                    })
+                    .collect();
+                self.evaluate(&x);
+            }
+            let center = match &self.best {
+                Some(b) if self.evaluations > evaluations => self.normalize(&b.values),
+                _ => break,
+            };
+            ranges = ranges
+                .iter()
+                .zip(center.iter())
+                .map(|((low, high), c)| {
+                    let quarter = (high - low) / 4.0;
+                    ((c - quarter).max(0.0), (c + quarter).min(1.0))
+                })
+                .collect();
+        }
+    }
+    /// Evaluate the random points
+    fn random(&mut self) {
+        let mut rng = StdRng::seed_from_u64(self.config.seed.unwrap_or(0));
+        for _ in 0..self.budget * 2 {
+            if self.exhausted() {
+                break;
+            }
+            let x: Vec<f64> = self.parameters.iter().map(|_| rng.gen::<f64>()).collect();
+            self.evaluate(&x);
+        }
+    }
+    /// The Nelder–Mead simplex method starting from the center of the ranges
+    fn nelder_mead(&mut self) {
+        let n = self.parameters.len();
+        let mut simplex: Vec<Candidate> = Vec::with_capacity(n + 1);
+        let center = vec![0.5; n];
+        simplex.push(self.evaluate(&center));
+        for i in 0..n {
+            if self.exhausted() {
+                return;
+            }
+            let mut x = center.clone();
+            x[i] += 0.25;
+            simplex.push(self.evaluate(&x));
+        }
+        let mut points: Vec<Vec<f64>> = (0..=n)
+            .map(|i| {
+                let mut x = center.clone();
+                if i > 0 {
+                    x[i - 1] += 0.25;
+                }
+                x
+            })
+            .collect();
+        for _ in 0..self.budget {
+            if self.exhausted() {
+                break;
+            }
+            let mut order: Vec<usize> = (0..=n).collect();
+            order.sort_by(|a, b| simplex[*a].compare(&simplex[*b]));
+            simplex = order.iter().map(|i| simplex[*i].clone()).collect();
+            points = order.iter().map(|i| points[*i].clone()).collect();
+            let size = points[1..]
+                .iter()
+                .flat_map(|p| p.iter().zip(points[0].iter()).map(|(a, b)| (a - b).abs()))
+                .fold(0.0, f64::max);
+            if size < TOLERANCE {
+                break;
+            }
+
+            let centroid: Vec<f64> = (0..n)
+                .map(|d| points[..n].iter().map(|p| p[d]).sum::<f64>() / n as f64)
+                .collect();
+            let toward = |t: f64, p: &[f64]| -> Vec<f64> {
+                centroid
+                    .iter()
+                    .zip(p.iter())
+                    .map(|(c, x)| (c + t * (x - c)).clamp(0.0, 1.0))
+                    .collect()
+            };
+            let reflected = toward(-1.0, &points[n]);
+            let r = self.evaluate(&reflected);
+            if self.exhausted() {
+                break;
+            }
+            if r.compare(&simplex[0]) == Ordering::Less {
+                let expanded = toward(-2.0, &points[n]);
+                let e = self.evaluate(&expanded);
+                if e.compare(&r) == Ordering::Less {
+                    simplex[n] = e;
+                    points[n] = expanded;
+                } else {
+                    simplex[n] = r;
+                    points[n] = reflected;
+                }
+            } else if r.compare(&simplex[n - 1]) == Ordering::Less {
+                simplex[n] = r;
+                points[n] = reflected;
+            } else {
+                let contracted = toward(0.5, &points[n]);
+                let c = self.evaluate(&contracted);
+                if c.compare(&simplex[n]) == Ordering::Less {
+                    simplex[n] = c;
+                    points[n] = contracted;
+                } else {
+                    for i in 1..=n {
+                        if self.exhausted() {
+                            break;
+                        }
+                        points[i] = points[i]
+                            .iter()
+                            .zip(points[0].iter())
+                            .map(|(x, b)| b + 0.5 * (x - b))
+                            .collect();
+                        simplex[i] = self.evaluate(&points[i]);
+                    }
+                }
+            }
+        }
+    }
+    /// The position of the values in the normalized ranges
+    fn normalize(&self, values: &[f64]) -> Vec<f64> {
+        self.parameters
+            .iter()
+            .zip(values.iter())
+            .map(|(p, v)| {
+                if p.max > p.min {
+                    (v - p.min) / (p.max - p.min)
+                } else {
+                    0.0
+                }
+            })
+            .collect()
+    }
+}
+
+/// Search the parameters in their ranges with the method in the budget of the games simulated,
+/// and return the best parameter set found with its metrics
+pub fn optimise(
+    config: &ScenarioConfig,
+    parameters: Vec<Parameter>,
+    objective: Objective,
+    constraints: Vec<Constraint>,
+    method: Method,
+    budget: usize,
+) -> Result<Optimum, Error> {
+    if parameters.is_empty() {
+        return Err(Error::ParameterError(
+            "there should be at least one parameter to optimise",
+        ));
+    }
+    if budget == 0 {
+        return Err(Error::ParameterError("the evaluations should be positive"));
+    }
+    let mut optimiser = Optimiser {
+        config,
+        parameters: &parameters,
+        objective: &objective,
+        constraints: &constraints,
+        budget,
+        evaluations: 0,
+        evaluated: Vec::new(),
+        best: None,
+        error: None,
+    };
+    match method {
+        Method::Grid => optimiser.grid(),
+        Method::Random => optimiser.random(),
+        Method::NelderMead => optimiser.nelder_mead(),
+    }
+    let evaluations = optimiser.evaluations;
+    match (optimiser.best, optimiser.error) {
+        (Some(best), _) => Ok(Optimum {
+            parameters,
+            objective,
+            constraints,
+            best,
+            evaluations,
+        }),
+        (None, Some(e)) => Err(e),
+        (None, None) => Err(Error::UnknownError("no parameter set is evaluated")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "challenge_linear.C=1..20".parse::<Parameter>().unwrap(),
+            Parameter {
+                path: "challenge_linear.C".to_string(),
+                min: 1.0,
+                max: 20.0,
+                integer: true,
+            }
+        );
+        assert!(!"F=1..2.5".parse::<Parameter>().unwrap().integer);
+        assert!("F=3..1".parse::<Parameter>().is_err());
+        assert!("F=3".parse::<Parameter>().is_err());
+        assert_eq!(
+            "max:attacker_loss".parse::<Objective>().unwrap(),
+            Objective {
+                metric: "attacker_loss".to_string(),
+                maximise: true,
+            }
+        );
+        assert!("min:speed".parse::<Objective>().is_err());
+        assert_eq!(
+            "honest_profit>=0".parse::<Constraint>().unwrap(),
+            Constraint {
+                metric: "honest_profit".to_string(),
+                at_least: true,
+                bound: 0.0,
+            }
+        );
+        assert!("duration=3".parse::<Constraint>().is_err());
+    }
+    #[test]
+    fn test_measure() {
+        // the attacker lies by the strategy, not by the choice
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10"
+			reward_function = "split"
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			strategy = { type = "adaptive", rule = "lie_until_bond", X = 10.0, rounds = 2 }
+			"#,
+        )
+        .unwrap();
+        let metrics = Metrics::measure(&config).unwrap();
+        assert_eq!(metrics.attacker_loss, 30.0);
+        assert_eq!(metrics.honest_profit, 0.0);
+    }
+    #[test]
+    fn test_optimise() {
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "linear"
+			reward_function = "split"
+
+			[bond_linear]
+			W = 1.0
+			C = 10.0
+			M = 100.0
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LL"
+
+			[[relayers]]
+			name = "Honest"
+			choice = "HHH"
+			"#,
+        )
+        .unwrap();
+        let parameters = vec!["bond_linear.C=1..100".parse::<Parameter>().unwrap()];
+        let objective = "min:max_bond".parse::<Objective>().unwrap();
+        let constraints = vec!["attacker_loss>=50".parse::<Constraint>().unwrap()];
+        for method in [Method::Grid, Method::Random, Method::NelderMead].iter() {
+            let optimum = optimise(
+                &config,
+                parameters.clone(),
+                objective.clone(),
+                constraints.clone(),
+                *method,
+                40,
+            )
+            .unwrap();
+            assert!(optimum.evaluations <= 40);
+            // the least base bond slashing the attacker no less than 50
+            assert!(optimum.best.feasible(), "{:?}", method);
+            assert_eq!(optimum.best.values, vec![24.0], "{:?}", method);
+            assert_eq!(optimum.best.metrics.attacker_loss, 51.0);
+            assert_eq!(optimum.patches(), vec!["bond_linear.C=24"]);
+        }
+
+        // the evaluations never exceed the budget, the simplex is shrunk in the small budgets
+        let parameters = vec![
+            "bond_linear.C=1..100".parse::<Parameter>().unwrap(),
+            "bond_linear.W=0..2".parse::<Parameter>().unwrap(),
+        ];
+        for budget in 1..20 {
+            let optimum = optimise(
+                &config,
+                parameters.clone(),
+                objective.clone(),
+                constraints.clone(),
+                Method::NelderMead,
+                budget,
+            )
+            .unwrap();
+            assert!(optimum.evaluations <= budget, "{}", budget);
+        }
+
+        let bad = vec!["unknown.C=1..2".parse::<Parameter>().unwrap()];
+        assert!(optimise(&config, bad, objective, vec![], Method::Grid, 5).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Patch module
+//! Patch any field of the scenario with the path of the field and the value, for example
+//! `F=3.5`, `challenge_linear.C=9` or `relayers[1].choice=LLH`.
+//!
+//! The patches are applied on the scenario before validation, the value is typed as the value
+//! patched, so `challenge_function=100` is still a string, and a field absent in the scenario is
+//! typed as a JSON value, such as `3.5`, `true`, `"3"` or `[1.0, 2.0]`, or a string otherwise.
+//! The patched scenario is parsed and validated again, so the wrong type is reported.
+use serde_json::Value;
+
+use crate::error::Error;
+
+/// # Segment
+/// The segment of the field path, `relayers[1].choice` is `relayers`, `1` and `choice`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// Parse the field path into segments
+pub fn parse_path(path: &str) -> Result<Vec<Segment>, Error> {
+    let invalid = || Error::PatchParameterError(format!("{}: invalid path", path));
+    let mut segments = Vec::new();
+    for part in path.trim().split('.') {
+        let (key, mut indexes) = match part.find('[') {
+            Some(i) => (&part[..i], &part[i..]),
+            None => (part, ""),
+        };
+        if key.is_empty() {
+            return Err(invalid());
+        }
+        segments.push(Segment::Key(key.to_string()));
+        while !indexes.is_empty() {
+            let end = indexes.find(']').ok_or_else(invalid)?;
+            let index = indexes[1..end].parse::<usize>().map_err(|_| invalid())?;
+            segments.push(Segment::Index(index));
+            indexes = &indexes[end + 1..];
+            if !indexes.is_empty() && !indexes.starts_with('[') {
+                return Err(invalid());
+            }
+        }
+    }
+    Ok(segments)
+}
+
+/// Format the segments as the field path
+pub fn format_path(segments: &[Segment]) -> String {
+    segments.iter().fold(String::new(), |p, s| match s {
+        Segment::Key(k) => join_key(p, k),
+        Segment::Index(i) => format!("{}[{}]", p, i),
+    })
+}
+
+pub fn join_key(parent: String, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// The field path of the key ignored in deserialization
+pub fn ignored_path(path: &serde_ignored::Path) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, index } => {
+            format!("{}[{}]", ignored_path(parent), index)
+        }
+        serde_ignored::Path::Map { parent, key } => join_key(ignored_path(parent), key),
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => ignored_path(parent),
+    }
+}
+
+/// Set the value of the field at the path, the tables absent are created, and the list can be
+/// appended with the index of its length, for example `relayers[2].choice=LLH` with two relayers
+pub fn set(value: &mut Value, path: &str, v: &str) -> Result<(), Error> {
+    let segments = parse_path(path)?;
+    let mut current = value;
+    for (i, segment) in segments.iter().enumerate() {
+        let at = || format_path(&segments[..=i]);
+        current = match segment {
+            Segment::Key(k) => {
+                if current.is_null() {
+                    *current = Value::Object(serde_json::Map::new());
+                }
+                current
+                    .as_object_mut()
+                    .ok_or_else(|| {
+                        Error::PatchParameterError(format!("{}: the parent is not a table", at()))
+                    })?
+                    .entry(k.clone())
+                    .or_insert(Value::Null)
+            }
+            Segment::Index(index) => {
+                let list = current.as_array_mut().ok_or_else(|| {
+                    Error::PatchParameterError(format!("{}: the parent is not a list", at()))
+                })?;
+                if *index == list.len() {
+                    list.push(Value::Null);
+                }
+                let len = list.len();
+                list.get_mut(*index).ok_or_else(|| {
+                    Error::PatchParameterError(format!(
+                        "{}: index out of range, the list has {} items",
+                        at(),
+                        len
+                    ))
+                })?
+            }
+        };
+    }
+    *current = typed(current, v)
+        .map_err(|e| Error::PatchParameterError(format!("{}: {}", format_path(&segments), e)))?;
+    Ok(())
+}
+
+/// Type the value as the value patched
+fn typed(old: &Value, v: &str) -> Result<Value, &'static str> {
+    match old {
+        Value::String(_) => Ok(Value::String(v.to_string())),
+        Value::Number(_) => serde_json::from_str::<serde_json::Number>(v)
+            .map(Value::Number)
+            .map_err(|_| "should be a number"),
+        Value::Bool(_) => v
+            .parse::<bool>()
+            .map(Value::Bool)
+            .map_err(|_| "should be true or false"),
+        _ => Ok(serde_json::from_str(v).unwrap_or_else(|_| Value::String(v.to_string()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("games[0].relayers[1].choice").unwrap(),
+            vec![
+                Segment::Key("games".to_string()),
+                Segment::Index(0),
+                Segment::Key("relayers".to_string()),
+                Segment::Index(1),
+                Segment::Key("choice".to_string()),
+            ]
+        );
+        assert!(parse_path("relayers[a]").is_err());
+        assert!(parse_path("relayers.").is_err());
+        assert!(parse_path("relayers[0]x").is_err());
+    }
+    #[test]
+    fn test_set() {
+        let mut v: Value = serde_json::from_str(
+            r#"{"De": 1000, "challenge_function": "linear", "relayers": [{"choice": "LL"}]}"#,
+        )
+        .unwrap();
+        set(&mut v, "challenge_function", "100").unwrap();
+        set(&mut v, "De", "500").unwrap();
+        set(&mut v, "F", "3.5").unwrap();
+        set(&mut v, "title", "What If").unwrap();
+        set(&mut v, "relayers[0].choice", "LH").unwrap();
+        set(&mut v, "relayers[1].choice", "HHH").unwrap();
+        set(&mut v, "treasury.R", "0.5").unwrap();
+        assert_eq!(
+            v,
+            serde_json::from_str::<Value>(
+                r#"{"De": 500, "F": 3.5, "title": "What If", "challenge_function": "100",
+                "relayers": [{"choice": "LH"}, {"choice": "HHH"}], "treasury": {"R": 0.5}}"#
+            )
+            .unwrap()
+        );
+        assert!(set(&mut v, "De", "many").is_err());
+        assert!(set(&mut v, "relayers[3].choice", "H").is_err());
+        assert!(set(&mut v, "title.x", "H").is_err());
+    }
+}
@@ -1,5 +1,9 @@
 use std::error::Error;
+use std::fs::File;
+use std::io::BufWriter;
 use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use plotters::drawing::{
     backend::{BackendStyle, DrawingErrorKind},
@@ -9,6 +13,12 @@ use plotters::prelude::*;
 use plotters::style::text_anchor::{HPos, VPos};
 use plotters::style::RGBAColor;
 
+use crate::chain::ChainsStatus;
+use crate::game::GameResult;
+
+/// The size of the charts written into the files
+static CHART_SIZE: (u32, u32) = (1024, 640);
+
 #[derive(Copy, Clone)]
 enum PixelState {
     Empty,
@@ -201,3 +211,374 @@ pub fn draw(title: &str, submit_round: usize, data: Vec<f64>) -> Result<(), Box<
     )?;
     Ok(())
 }
+
+/// # PNG Backend
+/// Draw the chart into an RGB pixel buffer and encode it as PNG when presented, instead of the
+/// bitmap backend of plotters 0.2, which dereferences misaligned pointers and crashes
+struct PngBackend {
+    file: PathBuf,
+    size: (u32, u32),
+    pixels: Vec<u8>,
+}
+
+impl PngBackend {
+    fn new(file: &Path, size: (u32, u32)) -> Self {
+        PngBackend {
+            file: file.to_path_buf(),
+            size,
+            pixels: vec![255; (size.0 * size.1 * 3) as usize],
+        }
+    }
+
+    fn encode(&self) -> Result<(), png::EncodingError> {
+        let file = BufWriter::new(File::create(&self.file)?);
+        let mut encoder = png::Encoder::new(file, self.size.0, self.size.1);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()?.write_image_data(&self.pixels)
+    }
+}
+
+impl DrawingBackend for PngBackend {
+    type ErrorType = png::EncodingError;
+
+    fn get_size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    fn ensure_prepared(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        Ok(())
+    }
+
+    fn present(&mut self) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        self.encode().map_err(DrawingErrorKind::DrawingError)
+    }
+
+    /// Blend the color with the alpha into the pixel, the pixels out of the chart are skipped
+    fn draw_pixel(
+        &mut self,
+        pos: (i32, i32),
+        color: &RGBAColor,
+    ) -> Result<(), DrawingErrorKind<Self::ErrorType>> {
+        let (w, h) = (self.size.0 as i32, self.size.1 as i32);
+        if pos.0 < 0 || pos.0 >= w || pos.1 < 0 || pos.1 >= h {
+            return Ok(());
+        }
+        let alpha = color.alpha();
+        let (r, g, b) = color.rgb();
+        let i = ((pos.1 * w + pos.0) * 3) as usize;
+        for (p, c) in self.pixels[i..i + 3].iter_mut().zip([r, g, b].iter()) {
+            *p = (f64::from(*p) * (1.0 - alpha) + f64::from(*c) * alpha).round() as u8;
+        }
+        Ok(())
+    }
+}
+
+/// # Format
+/// The format of the charts written into the files
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Svg,
+    Png,
+}
+
+impl FromStr for Format {
+    type Err = crate::error::Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "svg" => Ok(Format::Svg),
+            "png" => Ok(Format::Png),
+            _ => Err(crate::error::Error::CliError(format!(
+                "the chart format should be svg or png, not {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Format::Svg => "svg",
+            Format::Png => "png",
+        }
+    }
+}
+
+/// # Chart
+/// The labeled series drawn in a chart with axes and legends
+struct Chart {
+    /// The file name without extension
+    name: &'static str,
+    title: &'static str,
+    x_desc: &'static str,
+    y_desc: &'static str,
+    series: Vec<(String, Vec<(f64, f64)>)>,
+}
+
+impl Chart {
+    /// The ranges of the axes covering all the series, padded by 5%
+    fn ranges(&self) -> (Range<f64>, Range<f64>) {
+        let mut x = (f64::MAX, f64::MIN);
+        let mut y = (f64::MAX, f64::MIN);
+        for (px, py) in self.series.iter().flat_map(|s| s.1.iter()) {
+            x = (x.0.min(*px), x.1.max(*px));
+            y = (y.0.min(*py), y.1.max(*py));
+        }
+        let pad = |(min, max): (f64, f64)| {
+            if min > max {
+                0.0..1.0
+            } else if (max - min).abs() < f64::EPSILON {
+                min - 1.0..max + 1.0
+            } else {
+                let p = (max - min) * 0.05;
+                min - p..max + p
+            }
+        };
+        (pad(x), pad(y))
+    }
+
+    fn draw<DB: DrawingBackend>(
+        &self,
+        root: DrawingArea<DB, plotters::coord::Shift>,
+    ) -> Result<(), Box<dyn Error>>
+    where
+        DB::ErrorType: 'static,
+    {
+        root.fill(&WHITE)?;
+        let (x_range, y_range) = self.ranges();
+        let mut chart = ChartBuilder::on(&root)
+            .margin(20)
+            .caption(self.title, ("sans-serif", 30))
+            .x_label_area_size(50)
+            .y_label_area_size(80)
+            .build_ranged(x_range, y_range)?;
+        chart
+            .configure_mesh()
+            .x_desc(self.x_desc)
+            .y_desc(self.y_desc)
+            .draw()?;
+        for (i, (label, data)) in self.series.iter().enumerate() {
+            let color = Palette99::pick(i);
+            chart
+                .draw_series(LineSeries::new(data.clone(), &color))?
+                .label(label.as_str())
+                .legend(move |(x, y)| {
+                    PathElement::new(vec![(x, y), (x + 20, y)], &Palette99::pick(i))
+                });
+        }
+        chart
+            .configure_series_labels()
+            .background_style(&WHITE.mix(0.8))
+            .border_style(&BLACK)
+            .draw()?;
+        root.present()?;
+        Ok(())
+    }
+
+    fn save(&self, dir: &Path, format: Format) -> Result<PathBuf, Box<dyn Error>> {
+        let file = dir.join(format!("{}.{}", self.name, format.extension()));
+        match format {
+            Format::Svg => self.draw(SVGBackend::new(&file, CHART_SIZE).into_drawing_area())?,
+            Format::Png => self.draw(PngBackend::new(&file, CHART_SIZE).into_drawing_area())?,
+        }
+        Ok(file)
+    }
+}
+
+/// The charts of the challenge times, the bonds, the balances earned, the bond pool and the
+/// Ethereum blocks of each round.  The balances are settled after the last round, so the settled
+/// balances are drawn one round after the game.
+fn charts(result: &GameResult, chains_status: &ChainsStatus) -> [Chart; 5] {
+    let rounds = || {
+        result
+            .rounds
+            .iter()
+            .enumerate()
+            .map(|(i, r)| ((i + 1) as f64, r))
+    };
+
+    let settled = (result.rounds.len() + 1) as f64;
+    let mut balances: Vec<(String, Vec<(f64, f64)>)> = chains_status
+        .relayers
+        .iter()
+        .chain(chains_status.challengers.iter())
+        .map(|(id, s)| {
+            let mut data = vec![(0.0, 0.0)];
+            data.extend(rounds().filter_map(|(x, r)| r.balances.get(id).map(|b| (x, *b))));
+            data.push((settled, s.balance() - s.initial_balance));
+            (id.clone(), data)
+        })
+        .collect();
+    balances.sort_by(|a, b| a.0.cmp(&b.0));
+
+    [
+        Chart {
+            name: "challenge_times",
+            title: "Challenge Times",
+            x_desc: "Submit Round",
+            y_desc: "Darwinia Blocks",
+            series: vec![(
+                "Challenge Time".to_string(),
+                rounds()
+                    .map(|(x, r)| (x, r.challenge_time as f64))
+                    .collect(),
+            )],
+        },
+        Chart {
+            name: "bonds",
+            title: "Bonds",
+            x_desc: "Submit Round",
+            y_desc: "Bond",
+            series: vec![(
+                "Bond".to_string(),
+                rounds().map(|(x, r)| (x, r.bond)).collect(),
+            )],
+        },
+        Chart {
+            name: "balances",
+            title: "Cumulative Balances",
+            x_desc: "Submit Round (settled at the last point)",
+            y_desc: "Balance Earned",
+            series: balances,
+        },
+        Chart {
+            name: "bond_pool",
+            title: "Bond Pool",
+            x_desc: "Submit Round",
+            y_desc: "Bond Pool",
+            series: vec![(
+                "Bond Pool".to_string(),
+                rounds().map(|(x, r)| (x, r.submit_bond_pool)).collect(),
+            )],
+        },
+        Chart {
+            name: "ethereum_blocks",
+            title: "Ethereum Target Blocks",
+            x_desc: "Submit Round",
+            y_desc: "Ethereum Block",
+            series: vec![
+                (
+                    "Submitted".to_string(),
+                    rounds()
+                        .map(|(x, r)| (x, r.submit_ethereum_block as f64))
+                        .collect(),
+                ),
+                (
+                    "Next Target".to_string(),
+                    rounds()
+                        .map(|(x, r)| (x, r.target_ethereum_block as f64))
+                        .collect(),
+                ),
+            ],
+        },
+    ]
+}
+
+/// Write the charts of the game into the directory, and return the files written
+pub fn draw_files(
+    dir: &str,
+    format: Format,
+    result: &GameResult,
+    chains_status: &ChainsStatus,
+) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let dir = Path::new(dir);
+    std::fs::create_dir_all(dir)?;
+    charts(result, chains_status)
+        .iter()
+        .map(|c| c.save(dir, format))
+        .collect()
+}
+
+/// Draw the charts of the game as SVG documents, which can be inlined in HTML
+pub fn draw_svgs(
+    result: &GameResult,
+    chains_status: &ChainsStatus,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut svgs = Vec::new();
+    for c in charts(result, chains_status).iter() {
+        let mut svg = String::new();
+        c.draw(SVGBackend::with_string(&mut svg, CHART_SIZE).into_drawing_area())?;
+        svgs.push(svg);
+    }
+    Ok(svgs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::run;
+    use crate::scenario::ScenarioConfig;
+    #[test]
+    fn test_draw_files() {
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10"
+			reward_function = "split"
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LL"
+
+			[[relayers]]
+			name = "Honest"
+			choice = "HHH"
+			"#,
+        )
+        .unwrap();
+        let (chains_status, result) = run(&config).unwrap();
+        let dir = std::env::temp_dir().join(format!("refit-plot-{}", std::process::id()));
+        let files =
+            draw_files(dir.to_str().unwrap(), Format::Svg, &result, &chains_status).unwrap();
+        assert_eq!(files.len(), 5);
+        let balances = std::fs::read_to_string(dir.join("balances.svg")).unwrap();
+        assert!(balances.contains("Cumulative Balances"));
+        assert!(balances.contains("Honest"));
+        std::fs::remove_dir_all(dir).unwrap();
+
+        assert_eq!("SVG".parse::<Format>().unwrap(), Format::Svg);
+        assert_eq!("PNG".parse::<Format>().unwrap(), Format::Png);
+        assert!("gif".parse::<Format>().is_err());
+    }
+    #[test]
+    fn test_draw_png_files() {
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10"
+			reward_function = "split"
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LL"
+			"#,
+        )
+        .unwrap();
+        let (chains_status, result) = run(&config).unwrap();
+        let dir = std::env::temp_dir().join(format!("refit-png-{}", std::process::id()));
+        let files =
+            draw_files(dir.to_str().unwrap(), Format::Png, &result, &chains_status).unwrap();
+        assert_eq!(files.len(), 5);
+        assert_eq!(files[1], dir.join("bonds.png"));
+
+        let decoder = png::Decoder::new(File::open(&files[1]).unwrap());
+        let (info, mut reader) = decoder.read_info().unwrap();
+        assert_eq!((info.width, info.height), CHART_SIZE);
+        assert_eq!(info.color_type, png::ColorType::RGB);
+        let mut pixels = vec![0; info.buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        // the chart is drawn on the white background
+        assert_eq!(&pixels[..3], &[255, 255, 255]);
+        assert!(pixels.iter().any(|p| *p < 128));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
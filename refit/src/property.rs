@@ -0,0 +1,355 @@
+//! Property module
+//! Generate random valid scenarios with random choices, strategies, challengers, concurrent games,
+//! latency, header chains, transaction costs and equation parameters, play them through the
+//! engine and check the properties hold in every game
+//! - the funds are conserved, the balances earned, the treasury and the fee sum to zero, and no
+//!   fund is left in the bond pool after the games closed
+//! - no slash is paid twice, the slash shared in each round never exceeds the slash of the round,
+//!   and the rewards never exceed the bonds
+//! - the honest relayers never end with negative balance except for the fee under the `split`
+//!   reward
+//! - the game always ends
+//!
+//! The cases are generated from the seeds, so the failed case can be reproduced with its seed.
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use crate::chain::ChainsStatus;
+use crate::game::{play_concurrent, Game, GameResult};
+use crate::scenario::ScenarioConfig;
+
+/// The cases generated
+static CASES: u64 = 300;
+
+/// The max rounds of a game, the game not ending in the rounds is deemed as endless
+static MAX_ROUNDS: usize = 1000;
+
+/// The funds less than this are deemed as zero
+static EPSILON: f64 = 0.000_001;
+
+fn choice(rng: &mut StdRng, alphabet: &[char], len: usize) -> String {
+    (0..len).map(|_| *alphabet.choose(rng).unwrap()).collect()
+}
+
+/// The fields of the transaction cost
+fn transaction_cost(rng: &mut StdRng) -> String {
+    format!(
+        "B = {}, H = {}, Ms = {}, Mc = {}",
+        rng.gen_range(0.0, 5.0),
+        rng.gen_range(0.0, 1.0),
+        rng.gen_range(0.5, 2.0),
+        rng.gen_range(0.5, 2.0)
+    )
+}
+
+/// A participant with the choice or the strategy, and the optional latency and transaction cost
+fn participant(rng: &mut StdRng, table: &str, name: &str, alphabet: &[char], len: usize) -> String {
+    let mut s = format!("[[{}]]\nname = \"{}\"\n", table, name);
+    let relayer = alphabet.contains(&'H');
+    if rng.gen_bool(0.2) {
+        let rule = if relayer {
+            ["lie_until_bond", "profitable", "tit_for_tat"].choose(rng)
+        } else {
+            ["profitable", "tit_for_tat"].choose(rng)
+        };
+        if relayer && rng.gen_bool(0.3) {
+            s.push_str(&format!(
+                "strategy = {{ type = \"probabilistic\", P = {}, rounds = {} }}\n",
+                rng.gen_range(0.0, 1.0),
+                len
+            ));
+        } else {
+            s.push_str(&format!(
+                "strategy = {{ type = \"adaptive\", rule = \"{}\", X = {}, rounds = {} }}\n",
+                rule.unwrap(),
+                rng.gen_range(1.0, 100.0),
+                len
+            ));
+        }
+    } else {
+        s.push_str(&format!("choice = \"{}\"\n", choice(rng, alphabet, len)));
+    }
+    if rng.gen_bool(0.2) {
+        if rng.gen_bool(0.5) {
+            s.push_str(&format!(
+                "latency = {{ model = \"exponential\", mean = {} }}\n",
+                rng.gen_range(1.0, 50.0)
+            ));
+        } else {
+            s.push_str(&format!(
+                "latency = {{ model = \"fixed\", mean = {} }}\n",
+                rng.gen_range(0, 50)
+            ));
+        }
+    }
+    if rng.gen_bool(0.1) {
+        s.push_str(&format!(
+            "transaction_cost = {{ {} }}\n",
+            transaction_cost(rng)
+        ));
+    }
+    s
+}
+
+/// Generate a random scenario from the seed, the scenario may not be valid
+pub fn generate(seed: u64) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let de = rng.gen_range(1, 2000);
+    // the keys at the top level are placed before all the tables
+    let mut s = format!(
+        "target_function = \"half\"\nDd = {}\nDe = {}\nF = {}\nseed = {}\n",
+        rng.gen_range(1, 200),
+        de,
+        rng.gen_range(0.5, 4.0),
+        seed
+    );
+    let mut tables = String::new();
+    if rng.gen_bool(0.5) {
+        s.push_str("challenge_function = \"linear\"\n");
+        tables.push_str(&format!(
+            "[challenge_linear]\nWd = {}\nWe = {}\nC = {}\nMd = {}\nMe = {}\n",
+            rng.gen_range(0.0, 3.0),
+            rng.gen_range(0.0, 3.0),
+            rng.gen_range(0, 20),
+            rng.gen_range(1, 1000),
+            rng.gen_range(1, 1000),
+        ));
+    } else {
+        s.push_str(&format!(
+            "challenge_function = \"{}\"\n",
+            rng.gen_range(1, 100)
+        ));
+    }
+    if rng.gen_bool(0.5) {
+        s.push_str("bond_function = \"linear\"\n");
+        tables.push_str(&format!(
+            "[bond_linear]\nW = {}\nC = {}\nM = {}\n",
+            rng.gen_range(0.0, 3.0),
+            rng.gen_range(1.0, 100.0),
+            rng.gen_range(1.0, 500.0),
+        ));
+    } else {
+        s.push_str(&format!(
+            "bond_function = \"{}\"\n",
+            rng.gen_range(1.0, 100.0)
+        ));
+    }
+    if rng.gen_bool(0.5) {
+        s.push_str("reward_function = \"split\"\n");
+        tables.push_str(&format!(
+            "[reward_split]\nP = {}\n",
+            rng.gen_range(0.0, 1.0)
+        ));
+    } else {
+        s.push_str("reward_function = \"treasury_last\"\n");
+        tables.push_str(&format!(
+            "[reward_treasury_last]\nC = {}\n",
+            rng.gen_range(0.0, 200.0)
+        ));
+    }
+    if rng.gen_bool(0.3) {
+        tables.push_str(&format!(
+            "[treasury]\nR = {}\nS = {}\nI = {}\n",
+            rng.gen_range(0.0, 1.0),
+            rng.gen_range(0.0, 1.0),
+            rng.gen_range(0.0, 1000.0)
+        ));
+    }
+    if rng.gen_bool(0.2) {
+        tables.push_str(&format!(
+            "[header]\nD = {}\nT = {}\n",
+            rng.gen_range(2048, 4_000_000),
+            rng.gen_range(1, 30)
+        ));
+    }
+    if rng.gen_bool(0.3) {
+        tables.push_str(&format!(
+            "[transaction_cost]\n{}\n",
+            transaction_cost(&mut rng).replace(", ", "\n")
+        ));
+    }
+    s.push_str(&tables);
+
+    if rng.gen_bool(0.3) {
+        // the challengers are deemed as honest in the relayer-challenger(s) mode, so the relayer
+        // lies in the first submission challenged
+        let len = rng.gen_range(0, 5);
+        let relayer = format!("L{}", choice(&mut rng, &['H', 'L'], len));
+        s.push_str(&format!(
+            "[[relayers]]\nname = \"R0\"\nchoice = \"{}\"\n",
+            relayer
+        ));
+        for i in 0..rng.gen_range(1, 4) {
+            let len = rng.gen_range(1, relayer.len() + 1);
+            let name = format!("C{}", i);
+            s.push_str(&participant(
+                &mut rng,
+                "challengers",
+                &name,
+                &['0', '1'],
+                len,
+            ));
+        }
+    } else {
+        for i in 0..rng.gen_range(1, 5) {
+            let len = rng.gen_range(1, 7);
+            let name = format!("R{}", i);
+            s.push_str(&participant(
+                &mut rng,
+                "relayers",
+                &name,
+                &['H', 'L', 'N'],
+                len,
+            ));
+        }
+        // the concurrent games dispute on different blocks
+        let games = if rng.gen_bool(0.2) {
+            rng.gen_range(1, 3)
+        } else {
+            0
+        };
+        for g in 0..games {
+            s.push_str(&format!(
+                "[[games]]\nDd = {}\nDe = {}\n",
+                rng.gen_range(1, 200),
+                de + 1000 * (g + 1)
+            ));
+            for i in 0..rng.gen_range(1, 3) {
+                let len = rng.gen_range(1, 5);
+                let name = format!("R{}", i);
+                s.push_str(&participant(
+                    &mut rng,
+                    "games.relayers",
+                    &name,
+                    &['H', 'L', 'N'],
+                    len,
+                ));
+            }
+        }
+    }
+    s
+}
+
+/// Play the single game, return error if it does not end
+fn play(config: &ScenarioConfig, chains_status: &mut ChainsStatus) -> Result<GameResult, String> {
+    let mut game = Game::new(config, chains_status).map_err(|e| e.to_string())?;
+    let mut rounds = 0;
+    while game.step(chains_status, false) {
+        rounds += 1;
+        if rounds > MAX_ROUNDS {
+            return Err(format!("the game does not end in {} rounds", MAX_ROUNDS));
+        }
+    }
+    Ok(game.finish(chains_status))
+}
+
+/// Play the scenario and check the properties, return the violation if any
+pub fn check(config: &ScenarioConfig) -> Result<(), String> {
+    let mut chains_status: ChainsStatus = config.clone().into();
+    let results = if config.games.is_some() {
+        let mut configs = vec![config.clone()];
+        configs.append(
+            &mut config
+                .get_concurrent_scenarios()
+                .map_err(|e| e.to_string())?,
+        );
+        play_concurrent(&configs, &mut chains_status, false)
+            .map_err(|e| e.to_string())?
+            .games
+            .into_iter()
+            .map(|g| g.1)
+            .collect()
+    } else {
+        vec![play(config, &mut chains_status)?]
+    };
+
+    for (i, round) in results.iter().flat_map(|r| r.rounds.iter().enumerate()) {
+        if round.shared > round.slash + EPSILON {
+            return Err(format!(
+                "the slash is paid twice in round {}, {} shared from {} slash",
+                i + 1,
+                round.shared,
+                round.slash
+            ));
+        }
+    }
+    for r in results.iter() {
+        if r.unclaimed_slash < -EPSILON {
+            return Err(format!(
+                "the rewards exceed the bonds by {}",
+                -r.unclaimed_slash
+            ));
+        }
+    }
+    let pool = chains_status.submit_bond_pool
+        + chains_status
+            .games
+            .values()
+            .map(|g| g.submit_bond_pool)
+            .sum::<f64>();
+    if pool.abs() > EPSILON {
+        return Err(format!("{} is left in the bond pool", pool));
+    }
+
+    let participators = || {
+        chains_status
+            .relayers
+            .values()
+            .chain(chains_status.challengers.values())
+    };
+    let earned: f64 = participators()
+        .map(|p| p.balance() - p.initial_balance)
+        .sum();
+    let treasury = chains_status.treasury.slash_income - chains_status.treasury.paid;
+    let funds = earned + treasury + chains_status.transaction_fee;
+    if funds.abs() > EPSILON {
+        return Err(format!("the funds are not conserved, {} more", funds));
+    }
+
+    if config.reward_function.to_uppercase() == "SPLIT" {
+        // the relayers never lied, missed the challenge window or submitted a rejected header
+        let dishonest = |id: &str| {
+            results.iter().flat_map(|r| r.rounds.iter()).any(|r| {
+                r.submissions.iter().any(|(s, lie)| s == id && *lie)
+                    || r.missed.iter().chain(r.rejected.iter()).any(|m| m == id)
+            })
+        };
+        let honest = chains_status
+            .relayers
+            .values()
+            .filter(|r| !dishonest(&r.id))
+            .find(|r| r.balance() - r.initial_balance + r.fee < -EPSILON);
+        if let Some(r) = honest {
+            return Err(format!(
+                "the honest relayer {} ends with negative balance {}",
+                r.id,
+                r.balance() - r.initial_balance
+            ));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn test_random_scenarios() {
+        let mut valid = 0;
+        for seed in 0..CASES {
+            let scenario = generate(seed);
+            let config = match <ScenarioConfig>::from_str(&scenario) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+            valid += 1;
+            if let Err(e) = check(&config) {
+                panic!("seed {}: {}\n{}", seed, e, scenario);
+            }
+        }
+        // most of the scenarios generated should be valid
+        assert!(valid > CASES / 2, "only {} valid scenarios", valid);
+    }
+}
@@ -1,13 +1,13 @@
 use crate::chain::{Reward, RewardFrom};
 use crate::error::Error;
 use crate::reward::{ConfigValidate, Equation};
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// # Split reward equation
 /// slash value of submit round will take P as reward in current round, and leave (1-P) for the next
 /// round
 #[allow(non_snake_case)]
-#[derive(Default, Debug, Deserialize, Copy, Clone)]
+#[derive(Default, Debug, Deserialize, Serialize, Copy, Clone)]
 pub struct SplitConfig {
     /// P: the portion use in the current round, else will leave to next round
     P: f64,
@@ -16,20 +16,10 @@ pub struct SplitConfig {
 impl ConfigValidate for SplitConfig {
     fn validate(&self) -> Result<(), Error> {
         if self.P > 1.0 {
-            return Err(Error::ParameterError("P should not be greater than 1"));
+            return Err(Error::field("P", "should not be greater than 1"));
         }
         Ok(())
     }
-    fn apply_patch(&mut self, k: &str, v: &str) -> Result<(), Error> {
-        if k == "P" {
-            self.P = v.parse::<f64>()?;
-            Ok(())
-        } else {
-            Err(Error::PatchParameterError(
-                "parameter not correct".to_string(),
-            ))
-        }
-    }
 }
 
 impl Equation for SplitConfig {
@@ -56,14 +46,3 @@ impl Equation for SplitConfig {
         )
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_apply_patch() {
-        let mut c = SplitConfig::default();
-        c.apply_patch("P", "0.9").unwrap();
-        assert_eq!(c.P, 0.9);
-    }
-}
@@ -1,13 +1,13 @@
 use crate::chain::{Reward, RewardFrom};
 use crate::error::Error;
 use crate::reward::{ConfigValidate, Equation};
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /// # Treasure Last reward equation
 /// slash value of each submit round will pay for the honest relayer in the same round
 /// and the honest relayers in the last round will be payed from treasury
 #[allow(non_snake_case)]
-#[derive(Default, Debug, Deserialize, Copy, Clone)]
+#[derive(Default, Debug, Deserialize, Serialize, Copy, Clone)]
 pub struct TreasureLastConfig {
     /// C: the value will use to pay the honest relayers in the last round
     C: f64,
@@ -16,20 +16,10 @@ pub struct TreasureLastConfig {
 impl ConfigValidate for TreasureLastConfig {
     fn validate(&self) -> Result<(), Error> {
         if self.C < 0.0 {
-            return Err(Error::ParameterError("C should be greater than 0"));
+            return Err(Error::field("C", "should not be negative"));
         }
         Ok(())
     }
-    fn apply_patch(&mut self, k: &str, v: &str) -> Result<(), Error> {
-        if k == "C" {
-            self.C = v.parse::<f64>()?;
-            Ok(())
-        } else {
-            Err(Error::PatchParameterError(
-                "parameter not correct".to_string(),
-            ))
-        }
-    }
 }
 
 impl Equation for TreasureLastConfig {
@@ -41,13 +31,23 @@ impl Equation for TreasureLastConfig {
         honest_relayers: Vec<String>,
     ) -> (f64, Vec<Reward>) {
         let rewards = if current_slash == 0f64 {
+            // the bonds are returned from the bond pool, and the treasury only pays the share
             let share_for_honest_relayer = self.C / honest_relayers.len() as f64;
             honest_relayers
                 .into_iter()
-                .map(|r| Reward {
-                    from: RewardFrom::Treasure,
-                    to: r,
-                    value: share_for_honest_relayer + current_bond,
+                .flat_map(|r| {
+                    vec![
+                        Reward {
+                            from: RewardFrom::Slash,
+                            to: r.clone(),
+                            value: current_bond,
+                        },
+                        Reward {
+                            from: RewardFrom::Treasure,
+                            to: r,
+                            value: share_for_honest_relayer,
+                        },
+                    ]
                 })
                 .collect()
         } else {
@@ -64,14 +64,3 @@ impl Equation for TreasureLastConfig {
         (0f64, rewards)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    #[test]
-    fn test_apply_patch() {
-        let mut c = TreasureLastConfig::default();
-        c.apply_patch("C", "10.0").unwrap();
-        assert_eq!(c.C, 10.0);
-    }
-}
@@ -1,6 +1,7 @@
 use crate::sample::Equation;
 
 /// There is no parameter for Half Sampling Equation
+#[derive(Clone)]
 pub struct HalfConfig {}
 
 impl Equation for HalfConfig {
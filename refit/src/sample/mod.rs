@@ -8,10 +8,27 @@ use crate::error::Error;
 pub mod half;
 
 /// This trait help the main function calculate the next sampling block from the equation
-pub trait Equation {
+pub trait Equation: EquationClone {
     fn calculate(&self, relayed_header: usize, submit_header: usize) -> usize;
 }
 
+/// Clone the boxed equation, the equation should derive `Clone`
+pub trait EquationClone {
+    fn clone_box(&self) -> Box<dyn Equation>;
+}
+
+impl<T: 'static + Equation + Clone> EquationClone for T {
+    fn clone_box(&self) -> Box<dyn Equation> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Equation> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
 /// This trait help the main function validating the parameters when loading yaml
 pub trait ConfigValidate {
     fn validate(&self) -> Result<(), Error>;
@@ -6,12 +6,18 @@
 //!
 //! surfix d: block difference between last block number relayed on Darwinia,
 //! surfix e: block difference between last related block number of Ethereum
+use std::fs::File;
+use std::io::prelude::*;
 use std::iter::IntoIterator;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
-use serde_derive::Deserialize;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_derive::{Deserialize, Serialize};
 use toml;
 
+use crate::block_time::{BlockTimeConfig, LatencyConfig};
 use crate::bond::{
     linear::LinearConfig as BondLinear, ConfigValidate as BondVali, Equation as BondEq,
 };
@@ -19,18 +25,26 @@ use crate::challenge::{
     linear::LinearConfig as ChallengeLinear, ConfigValidate as ChallengeVali,
     Equation as ChallengeEq,
 };
+use crate::cost::TransactionCostConfig;
 use crate::error::Error;
+use crate::header::{parse_headers, Header, HeaderConfig};
+use crate::patch;
 use crate::reward::{
     split::SplitConfig, treasury_last::TreasureLastConfig, ConfigValidate as RewardVali,
     Equation as RewardEq,
 };
 use crate::sample::{half::HalfConfig, Equation as TargetEq};
+use crate::strategy::{Action, Observation, Scripted, Strategy, StrategyConfig};
+use crate::treasury::TreasuryConfig;
+
+/// The block time of Darwinia in seconds
+static DARWINIA_BLOCK_TIME: f64 = 6.0;
 
 /// # Scenario Config
 /// In this config, the `challenge_function`, the initial status, and the `relayers` are defined.
 /// The initial status contains the block difference in the target chain and Darwinia chain.
 #[allow(non_snake_case)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ScenarioConfig {
     pub title: Option<String>,
     /// Dd: (optional) the initial block difference between last block number relayed on Darwinia, default 0
@@ -42,6 +56,33 @@ pub struct ScenarioConfig {
     /// For example, 2.0 means that darwinia produce 2 blocks and ethereum produce 1 block.
     pub F: Option<f64>,
 
+    /// The block time models of Darwinia and Ethereum, F is not used if the block time is set
+    pub block_time: Option<BlockTimeConfig>,
+
+    /// The synthetic header chain of target chain, the submissions are simulated as block numbers
+    /// if it is not set
+    pub header: Option<HeaderConfig>,
+
+    /// The JSON file of Ethereum headers as returned by `eth_getBlockByNumber`, the path is
+    /// relative to the scenario file.  The headers are the canonical Ethereum chain, the first
+    /// header is the last relayed block, De and F are from the headers if they are not set.
+    pub ethereum_headers: Option<String>,
+
+    /// The Ethereum headers imported from `ethereum_headers`
+    #[serde(skip)]
+    pub canonical_headers: Option<Vec<Header>>,
+
+    /// The scenario before validation, the patches are applied on it
+    #[serde(skip)]
+    pub(crate) source: Option<serde_json::Value>,
+
+    /// The directory of the scenario file, the `ethereum_headers` is relative to it
+    #[serde(skip)]
+    dir: Option<PathBuf>,
+
+    /// The seed of the random number generator for the latency of participators, default 0
+    pub seed: Option<u64>,
+
     /// Once a relayer submit a header and wait the challenge time in blocks after the calculated value equation from challenge
     /// function, Darwinia network will deem this header is validated and become a last relayed header.
     pub challenge_function: String,
@@ -68,17 +109,32 @@ pub struct ScenarioConfig {
     /// parameters in treasury reward the last submit round
     pub reward_treasury_last: Option<TreasureLastConfig>,
 
+    /// parameters of treasury, the fee income from redeem actions and the slash routed to treasury
+    pub treasury: Option<TreasuryConfig>,
+
+    /// parameters of the fee for each extrinsic of submission or challenge
+    pub transaction_cost: Option<TransactionCostConfig>,
+
     /// The relayers participate in these game
     /// We suppose that there is always a honest relayer provided by Darwinia,
     /// so after the config correctly imported, the Darwinia relayer will add into.
+    #[serde(default)]
     pub relayers: Vec<RelayerConfig>,
 
     /// current challenge fee is the same with bond function
     /// challenger list (current implementation allow only one challenger)
     pub challengers: Option<Vec<ChallengerConfig>>,
+
+    /// The participants with roles, they are added into the relayers and the challengers by
+    /// their roles after the config correctly imported
+    pub participants: Option<Vec<ParticipantConfig>>,
+
+    /// The games played concurrently with the game described above, each game disputes on a
+    /// different Ethereum block, and the relayers with the same id are the same relayer
+    pub games: Option<Vec<GameConfig>>,
 }
 
-#[derive(Default)]
+#[derive(Default, Debug, Clone)]
 pub struct RelayPositions {
     pub geneisis: usize,
     pub relay_blocks: Vec<Vec<usize>>,
@@ -87,18 +143,24 @@ pub struct RelayPositions {
 impl RelayPositions {
     pub fn plot(&self) -> String {
         let mut output = "G".to_string();
-        let max_relay_block = if self.relay_blocks.len() > 0 {
-            self.relay_blocks[0][0]
-        } else {
-            1
-        };
+        let max_relay_block = self
+            .relay_blocks
+            .iter()
+            .flatten()
+            .map(|b| b.saturating_sub(self.geneisis))
+            .max()
+            .unwrap_or_default()
+            .max(1);
         let block_indece: Vec<Vec<usize>> = self
             .relay_blocks
             .clone()
             .into_iter()
             .map(|vs| {
                 vs.into_iter()
-                    .map(|v| ((v as f64 / max_relay_block as f64) * 64.0) as usize)
+                    .map(|v| {
+                        ((v.saturating_sub(self.geneisis) as f64 / max_relay_block as f64) * 64.0)
+                            as usize
+                    })
                     .collect()
             })
             .collect();
@@ -126,9 +188,27 @@ impl RelayPositions {
     }
 }
 
+#[derive(Clone)]
 pub struct ScenarioConfigIntoIterator {
-    relayers: Vec<RelayerConfig>,
+    relayers: Vec<(String, Box<dyn Strategy>)>,
+    rounds: usize,
     pub submit_round: usize,
+    /// The submissions of the previous round
+    previous: Vec<(String, bool)>,
+}
+
+impl From<&[RelayerConfig]> for ScenarioConfigIntoIterator {
+    fn from(relayers: &[RelayerConfig]) -> Self {
+        ScenarioConfigIntoIterator {
+            relayers: relayers
+                .iter()
+                .map(|r| (r.id().to_string(), r.build_strategy(Role::Relayer)))
+                .collect(),
+            rounds: relayers.first().map(|r| r.choice.len()).unwrap_or_default(),
+            submit_round: 0,
+            previous: Vec::new(),
+        }
+    }
 }
 
 impl IntoIterator for ScenarioConfig {
@@ -136,20 +216,226 @@ impl IntoIterator for ScenarioConfig {
     type IntoIter = ScenarioConfigIntoIterator;
 
     fn into_iter(self) -> Self::IntoIter {
-        ScenarioConfigIntoIterator {
-            relayers: self.relayers,
-            submit_round: 0,
-        }
+        self.relayers[..].into()
     }
 }
 
 impl ScenarioConfig {
     /// a method to get iterator with less clone, and save memory
     pub fn get_iter(&self) -> ScenarioConfigIntoIterator {
-        ScenarioConfigIntoIterator {
-            relayers: self.relayers.clone(),
-            submit_round: 0,
+        self.relayers[..].into()
+    }
+    /// Set the choice of the participant instead of the strategy, and the Darwinia relayer keeps
+    /// submitting till the end of the game in relayers-only mode
+    pub fn set_choice(&mut self, id: &str, choice: &str) -> Result<(), Error> {
+        let p = self
+            .relayers
+            .iter_mut()
+            .chain(self.challengers.iter_mut().flatten())
+            .find(|p| p.id() == id && id != "Darwinia")
+            .ok_or(Error::ParameterError("the participant is not in the scenario"))?;
+        p.choice = choice.to_string();
+        p.strategy = None;
+        if self.challengers.is_none() {
+            let max_chose = self.relayers[1..]
+                .iter()
+                .map(|r| r.choice.len())
+                .max()
+                .unwrap_or_default();
+            self.relayers[0].choice = "H".repeat(max_chose + 1);
         }
+        Ok(())
+    }
+    /// The scenarios of the concurrent games, which are the same as this scenario except for the
+    /// initial status and the relayers
+    pub fn get_concurrent_scenarios(&self) -> Result<Vec<ScenarioConfig>, Error> {
+        let mut scenarios = Vec::new();
+        for (i, g) in self
+            .games
+            .clone()
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            let paths: Vec<String> = (0..g.relayers.len())
+                .map(|j| format!("games[{}].relayers[{}]", i, j))
+                .collect();
+            let mut relayers = g.relayers;
+            let (max_chose, problems) = self.resolve_relayers(&mut relayers, &paths);
+            if let Some(e) = problems.into_iter().next() {
+                return Err(e);
+            }
+            add_darwinia_relayer(&mut relayers, max_chose);
+            scenarios.push(ScenarioConfig {
+                Dd: g.Dd,
+                De: g.De,
+                relayers,
+                games: None,
+                ..self.clone()
+            });
+        }
+        Ok(scenarios)
+    }
+    /// Resolve the choices of the participants with strategies, the choices of challengers are
+    /// resolved against the choice of the relayer, and the adaptive participants decide in the
+    /// game, so their choices are honest as placeholders of the rounds
+    fn resolve_strategies(
+        &self,
+        participants: &mut [ParticipantConfig],
+        paths: &[String],
+        relayer_choice: Option<&str>,
+    ) -> Vec<Error> {
+        let default_rounds = match relayer_choice {
+            Some(choice) => choice.len(),
+            None => participants
+                .iter()
+                .filter(|p| p.strategy.is_none())
+                .map(|p| p.choice.len())
+                .max()
+                .unwrap_or_default(),
+        };
+        // the honest and the lying choice of the round
+        let choices = |round: usize| match relayer_choice {
+            Some(choice) if choice.as_bytes().get(round) == Some(&b'L') => ('0', '1'),
+            Some(_) => ('1', '0'),
+            None => ('H', 'L'),
+        };
+        let mut rng = StdRng::seed_from_u64(self.seed.unwrap_or(0));
+        let mut problems = Vec::new();
+        for (p, path) in participants.iter_mut().zip(paths.iter()) {
+            let strategy = match &p.strategy {
+                Some(s) => s,
+                None => continue,
+            };
+            if !p.choice.is_empty() {
+                problems.push(Error::field(
+                    &patch::join_key(path.to_string(), "choice"),
+                    "choice should not be set with strategy",
+                ));
+                continue;
+            }
+            if let Err(e) = strategy.validate() {
+                problems.push(e.in_table(&patch::join_key(path.to_string(), "strategy")));
+                continue;
+            }
+            let rounds = 0..strategy.rounds().unwrap_or(default_rounds);
+            p.choice = match strategy {
+                StrategyConfig::Scripted { choice } => choice.clone(),
+                StrategyConfig::Probabilistic { P, .. } => rounds
+                    .map(|i| {
+                        let (honest, lie) = choices(i);
+                        if rng.gen::<f64>() < *P {
+                            lie
+                        } else {
+                            honest
+                        }
+                    })
+                    .collect(),
+                StrategyConfig::Adaptive { .. } => rounds.map(|i| choices(i).0).collect(),
+            };
+        }
+        problems
+    }
+    /// Resolve the strategies of the relayers at the field paths, validate and identify them,
+    /// return the max length of choices and the problems found
+    fn resolve_relayers(
+        &self,
+        relayers: &mut [RelayerConfig],
+        paths: &[String],
+    ) -> (usize, Vec<Error>) {
+        let mut problems = self.resolve_strategies(relayers, paths, None);
+        let mut max_chose = 0;
+        for (r, path) in relayers.iter_mut().zip(paths.iter()) {
+            r.choice.make_ascii_uppercase();
+            problems.append(&mut relayer_problems(path, r));
+            max_chose = std::cmp::max(max_chose, r.choice.len());
+        }
+        problems.append(&mut identify(relayers, paths, Role::Relayer));
+        (max_chose, problems)
+    }
+    /// Load the Ethereum headers from the file of `ethereum_headers` in the directory
+    pub fn load_ethereum_headers(&mut self, dir: &Path) -> Result<(), Error> {
+        if let Some(f) = &self.ethereum_headers {
+            let mut file = File::open(dir.join(f))?;
+            let mut contents = String::new();
+            file.read_to_string(&mut contents)?;
+            self.set_ethereum_headers(parse_headers(&contents)?)?;
+        }
+        Ok(())
+    }
+    /// Use the headers as the canonical Ethereum chain, De is the blocks after the first header,
+    /// and F is the Ethereum block time over the Darwinia block time, if they are not set
+    pub fn set_ethereum_headers(&mut self, headers: Vec<Header>) -> Result<(), Error> {
+        if headers.is_empty() {
+            return Err(Error::HeaderFixtureError(
+                "no Ethereum header is imported".to_string(),
+            ));
+        }
+        let max_de = headers.len() - 1;
+        if self.De.unwrap_or(max_de) > max_de
+            || self
+                .games
+                .iter()
+                .flatten()
+                .any(|g| g.De.unwrap_or(100) > max_de)
+        {
+            return Err(Error::ParameterError(
+                "De should not exceed the imported Ethereum headers",
+            ));
+        }
+        if self.De.is_none() {
+            self.De = Some(max_de);
+        }
+        if self.F.is_none() && max_de > 0 {
+            let ethereum_block_time =
+                (headers[max_de].timestamp - headers[0].timestamp) as f64 / max_de as f64;
+            self.F = Some(ethereum_block_time / DARWINIA_BLOCK_TIME);
+        }
+        self.canonical_headers = Some(headers);
+        // the disputed blocks of the concurrent games are moved with the genesis
+        if let Some(e) = self.disputed_block_problems().into_iter().next() {
+            return Err(e);
+        }
+        Ok(())
+    }
+    /// The concurrent games should dispute on different Ethereum blocks
+    fn disputed_block_problems(&self) -> Vec<Error> {
+        let sample = |de: Option<usize>| self.sample_disputed_block(de).ok();
+        let mut disputed_blocks: Vec<usize> = sample(self.De).into_iter().collect();
+        let mut problems = Vec::new();
+        for (i, g) in self.games.iter().flatten().enumerate() {
+            if let Some(disputed_block) = sample(g.De) {
+                if disputed_blocks.contains(&disputed_block) {
+                    problems.push(Error::FieldError(
+                        format!("games[{}].De", i),
+                        format!(
+                            "the game disputes on the same block #{} as another game",
+                            disputed_block
+                        ),
+                    ));
+                }
+                disputed_blocks.push(disputed_block);
+            }
+        }
+        problems
+    }
+    /// The last relayed Ethereum block when the simulation starts
+    pub fn ethereum_genesis(&self) -> usize {
+        self.canonical_headers
+            .as_ref()
+            .map(|hs| hs[0].number)
+            .unwrap_or_default()
+    }
+    /// The disputed Ethereum block of the game
+    pub fn disputed_block(&self) -> usize {
+        self.sample_disputed_block(self.De).unwrap()
+    }
+    /// The disputed Ethereum block sampled from the genesis to De blocks after it
+    fn sample_disputed_block(&self, de: Option<usize>) -> Result<usize, Error> {
+        let genesis = self.ethereum_genesis();
+        Ok(self
+            .get_sample_equation()?
+            .calculate(genesis, genesis + de.unwrap_or(100)))
     }
     pub fn get_challenge_equation(&self) -> Result<Box<dyn ChallengeEq>, Error> {
         if let Ok(i) = self.challenge_function.as_str().parse::<usize>() {
@@ -215,199 +501,625 @@ impl ScenarioConfig {
             "lack prameters for specified reward function",
         ));
     }
+    /// Apply the patches as `path=value`, for example `F=3.5` or `relayers[1].choice=LLH`, on the
+    /// current scenario before validation, then parse and validate the patched scenario again.
+    /// The Ethereum headers are reloaded if `ethereum_headers` is patched.
     pub fn apply_patch(&mut self, patches: Vec<&str>) -> Result<(), Error> {
-        let split_with_equation = patches.iter().map(|patch| {
-            let mut i = patch.split('=').into_iter();
-            return (i.clone().nth(0), i.nth(1));
-        });
-        for key_values in split_with_equation {
-            if let (Some(k), Some(v)) = key_values {
-                let para = k.split('.').nth(1);
-                if k.starts_with("challenge_linear") {
-                    let p = para.ok_or_else(|| {
-                        Error::PatchParameterError(
-                            "parameters of challenge linear are absent".to_string(),
-                        )
-                    })?;
-                    let mut w = self.challenge_linear.ok_or_else(|| {
-                        Error::PatchParameterError("challenge linear absent".to_string())
-                    })?;
-                    w.apply_patch(p, v)?;
-                    w.validate()?;
-                    self.challenge_linear = Some(w);
-                } else if k.starts_with("bond_linear") {
-                    let p = para.ok_or_else(|| {
-                        Error::PatchParameterError(
-                            "parameters of bond linear are absent".to_string(),
-                        )
-                    })?;
-                    let mut f = self.bond_linear.ok_or_else(|| {
-                        Error::PatchParameterError("bond linear absent".to_string())
-                    })?;
-                    f.apply_patch(p, v)?;
-                    f.validate()?;
-                    self.bond_linear = Some(f);
-                } else if k.starts_with("reward_split") {
-                    let p = para.ok_or_else(|| {
-                        Error::PatchParameterError(
-                            "parameter of reward split parameter is absent".to_string(),
-                        )
-                    })?;
-                    let mut f = self.reward_split.ok_or_else(|| {
-                        Error::PatchParameterError("reward split config absent".to_string())
-                    })?;
-                    f.apply_patch(p, v)?;
-                    f.validate()?;
-                    self.reward_split = Some(f);
-                } else if k.starts_with("reward_treasury_last") {
-                    let p = para.ok_or_else(|| {
-                        Error::PatchParameterError(
-                            "parameter of reward treasury last model is absent".to_string(),
-                        )
-                    })?;
-                    let mut f = self.reward_treasury_last.ok_or_else(|| {
-                        Error::PatchParameterError("reward treasury last config absent".to_string())
-                    })?;
-                    f.apply_patch(p, v)?;
-                    f.validate()?;
-                    self.reward_treasury_last = Some(f);
-                } else if k.starts_with("challenge_function") {
-                    self.challenge_function = v.to_string();
-                } else if k.starts_with("bond_function") {
-                    self.bond_function = v.to_string();
-                } else if k.starts_with("reward_function") {
-                    self.reward_function = v.to_string();
+        if patches.is_empty() {
+            return Ok(());
+        }
+        let mut value = self.current_value()?;
+        let mut paths = Vec::new();
+        for patch in patches {
+            let mut kv = patch.splitn(2, '=');
+            match (kv.next(), kv.next()) {
+                (Some(k), Some(v)) => {
+                    patch::set(&mut value, k, v)?;
+                    paths.push(patch::format_path(&patch::parse_path(k)?));
                 }
-            } else {
-                return Err(Error::PatchParameterError(
-                    key_values.0.unwrap_or_default().to_string(),
-                ));
+                _ => return Err(Error::PatchParameterError(patch.to_string())),
             }
         }
+
+        let mut unknown_keys = Vec::new();
+        let mut callback = |p: serde_ignored::Path| unknown_keys.push(patch::ignored_path(&p));
+        let c: Result<ScenarioConfig, _> = serde_path_to_error::deserialize(
+            serde_ignored::Deserializer::new(value.clone(), &mut callback),
+        );
+        if let Some(k) = unknown_keys
+            .iter()
+            .find(|k| paths.iter().any(|p| p == *k || p.starts_with(&format!("{}.", k))))
+        {
+            return Err(Error::PatchParameterError(format!("{}: unknown path", k)));
+        }
+        let mut c = c
+            .map_err(|e| {
+                Error::PatchParameterError(format!("{}: {}", e.path(), e.inner()))
+            })?
+            .with_source(value)
+            .validate()?;
+        c.dir = self.dir.take();
+        if paths.iter().any(|p| p == "ethereum_headers") {
+            let dir = c.dir.clone().unwrap_or_else(|| PathBuf::from("."));
+            c.load_ethereum_headers(&dir)?;
+        } else if let Some(headers) = self.canonical_headers.take() {
+            c.set_ethereum_headers(headers)?;
+        }
+        *self = c;
         Ok(())
     }
+    /// The scenario before validation with the fields changed after it is loaded, the relayers,
+    /// the challengers and the games are resolved in validation, so their changes are refused
+    fn current_value(&self) -> Result<serde_json::Value, Error> {
+        let mut value = self.source.clone().ok_or_else(|| {
+            Error::PatchParameterError("the scenario is not loaded from a file".to_string())
+        })?;
+        let mut loaded = serde_json::from_value::<ScenarioConfig>(value.clone())
+            .map_err(|e| Error::PatchParameterError(format!("{}", e)))?
+            .with_source(value.clone())
+            .validate()?;
+        if let Some(headers) = self.canonical_headers.clone() {
+            loaded.set_ethereum_headers(headers)?;
+        }
+        let to_value = |c: &ScenarioConfig| {
+            serde_json::to_value(c).map_err(|e| Error::PatchParameterError(format!("{}", e)))
+        };
+        let (loaded, current) = (to_value(&loaded)?, to_value(self)?);
+        for (key, v) in current.as_object().into_iter().flatten() {
+            if loaded.get(key) == Some(v) {
+                continue;
+            }
+            if ["relayers", "challengers", "participants", "games"].contains(&key.as_str()) {
+                return Err(Error::PatchParameterError(format!(
+                    "{}: changed after the scenario is loaded, patch it instead",
+                    key
+                )));
+            }
+            if let Some(table) = value.as_object_mut() {
+                table.insert(key.clone(), v.clone());
+            }
+        }
+        Ok(value)
+    }
+}
+
+impl ScenarioConfigIntoIterator {
+    /// Return the submissions of relayers in the next round, and the relayers decide with the
+    /// game state observed
+    pub fn next_observed<F>(&mut self, observe: F) -> Option<Vec<(String, bool)>>
+    where
+        F: Fn(&str) -> Observation,
+    {
+        if self.submit_round >= self.rounds {
+            return None;
+        }
+        self.submit_round += 1;
+        let mut submissions = Vec::new();
+        for (id, strategy) in self.relayers.iter_mut() {
+            let observation = Observation {
+                round: self.submit_round,
+                previous: self
+                    .previous
+                    .iter()
+                    .filter(|m| m.0 != *id)
+                    .cloned()
+                    .collect(),
+                ..observe(id)
+            };
+            match strategy.decide(&observation) {
+                Action::Honest => submissions.push((id.clone(), false)),
+                Action::Lie => submissions.push((id.clone(), true)),
+                Action::NoResponse => {}
+            }
+        }
+        self.previous = submissions.clone();
+        Some(submissions)
+    }
 }
 
 impl Iterator for ScenarioConfigIntoIterator {
     type Item = Vec<(String, bool)>;
     /// Return the the relayer is liing in each round
     fn next(&mut self) -> Option<Vec<(String, bool)>> {
-        if self.submit_round >= self.relayers[0].choice.len() {
-            return None;
-        }
-        let current_index = self.submit_round;
-        self.submit_round += 1;
-        Some(
-            self.relayers
-                .iter()
-                .map(|r| {
-                    if current_index >= r.choice.len() {
-                        Err("this replay has no response")
-                    } else {
-                        Ok((
-                            r.name.clone().unwrap(),
-                            r.choice.chars().nth(current_index).unwrap() == 'L',
-                        ))
-                    }
-                })
-                .filter_map(Result::ok)
-                .collect(),
-        )
+        self.next_observed(|_| Observation::default())
     }
 }
 
-/// RelayerConfig
+/// # Participant Config
 /// This config is used for Relayer or Challenger
-/// Set up a `name` and the `choice` about the relayer
-#[derive(Debug, Deserialize, Clone)]
-pub struct RelayerConfig {
-    /// Optional field help you to know the relayer in
+/// Set up a `name` and the `choice` or the `strategy` about the participant
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ParticipantConfig {
+    /// The stable identifier of the participant, default the name, or the role and the index in
+    /// the list, for example `relayer-0` or `challenger-1`
+    pub id: Option<String>,
+    /// Optional field help you to know the participant in
     pub name: Option<String>,
-    /// The client can choice to be Honest(H), Lie(L), No response(N), if the choice is not lone as
-    /// other replayer, it will be automaticaly no response
+    /// The role of the participant, only required in `participants`
+    pub role: Option<Role>,
+    /// The relayer can choice to be Honest(H), Lie(L), No response(N), if the choice is not lone as
+    /// other replayer, it will be automaticaly no response.
+    /// The challenger can choice to challenge(0) or confirm(1) the submission in each round.
+    #[serde(default)]
     pub choice: String,
+    /// Optional strategy to resolve the choice, the `choice` should not be set with it
+    pub strategy: Option<StrategyConfig>,
+    /// The initial balance of the participant, default 0
+    pub balance: Option<f64>,
+    /// Optional latency of submissions in Darwinia blocks, the submission arriving after the
+    /// challenge window closed is deemed as no response
+    pub latency: Option<LatencyConfig>,
+    /// Optional fee of the extrinsics sent by the participant, instead of the `transaction_cost`
+    /// of the scenario
+    pub transaction_cost: Option<TransactionCostConfig>,
 }
 
-/// ChallengerConfig
-/// This config is used for Relayer or Challenger
-/// Set up a `name` and the `choice` about the relayer
-#[derive(Debug, Deserialize, Clone)]
-pub struct ChallengerConfig {
-    /// Optional field help you to know the relayer in
-    pub name: Option<String>,
-    /// The client can choice to be Honest(H), Lie(L), No response(N), if the choice is not lone as
-    /// other replayer, it will be automaticaly no response
-    pub choice: String,
+pub type RelayerConfig = ParticipantConfig;
+pub type ChallengerConfig = ParticipantConfig;
+
+impl ParticipantConfig {
+    /// The identifier of the participant, it is always set after the config correctly imported
+    pub fn id(&self) -> &str {
+        self.id.as_deref().unwrap_or_default()
+    }
+    /// The strategy of the participant in the role, the choice is scripted without a strategy
+    pub fn build_strategy(&self, role: Role) -> Box<dyn Strategy> {
+        match &self.strategy {
+            Some(s) => s.build(role, &self.choice),
+            None => Box::new(Scripted::from(self.choice.as_str())),
+        }
+    }
 }
 
-impl FromStr for ScenarioConfig {
-    type Err = Error;
-    /// Pase from the scenario toml file as listed in `/scenario `
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut c: ScenarioConfig = toml::from_str(s)?;
-        c.challenge_function.make_ascii_uppercase();
-        if let Some(w) = c.challenge_linear {
-            w.validate()?;
+/// # Role
+/// The role of the participant
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Relayer,
+    Challenger,
+}
+
+impl Role {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Role::Relayer => "relayer",
+            Role::Challenger => "challenger",
+        }
+    }
+}
+
+/// GameConfig
+/// This config is used for a concurrent game, the equations are the same as the scenario
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GameConfig {
+    /// Dd: (optional) the Darwinia blocks after the last relayed block when the game starts, default 0
+    pub Dd: Option<usize>,
+    /// De: the block difference between last related block number of Ethereum, the disputed
+    /// block is sampled from this, so each game should have a different De
+    pub De: Option<usize>,
+    /// The relayers participate in this game, the Darwinia relayer will add into.
+    pub relayers: Vec<RelayerConfig>,
+}
+
+/// # Format
+/// The scenario file can be TOML, YAML or JSON with the same schema
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Toml,
+    Yaml,
+    Json,
+}
+
+impl Format {
+    /// Pick the format from the file extension, the `.yml` or `.yaml` file and the file without
+    /// known extension are detected from the content, because the TOML files were named as `.yml`
+    pub fn from_file(file_name: &str, content: &str) -> Self {
+        match Path::new(file_name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref()
+        {
+            Some("toml") => Format::Toml,
+            Some("json") => Format::Json,
+            _ => Format::from_content(content),
         }
-        if let Some(f) = c.bond_linear {
-            f.validate()?;
+    }
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Toml => "TOML",
+            Format::Yaml => "YAML",
+            Format::Json => "JSON",
         }
-        if let Some(r) = c.reward_split {
-            r.validate()?;
+    }
+    /// JSON starts with `{`, and YAML is not valid TOML
+    pub fn from_content(content: &str) -> Self {
+        if content.trim_start().starts_with('{') {
+            Format::Json
+        } else if content.parse::<toml::Value>().is_ok() {
+            Format::Toml
+        } else {
+            Format::Yaml
         }
+    }
+}
 
-        let mut max_chose = 0;
+impl ScenarioConfig {
+    /// Parse the scenario in the format, and validate it
+    pub fn from_format(s: &str, format: Format) -> Result<Self, Error> {
+        let c: ScenarioConfig = match format {
+            Format::Toml => toml::from_str(s)?,
+            Format::Yaml => serde_yaml::from_str(s)?,
+            Format::Json => serde_json::from_str(s)
+                .map_err(|e| Error::ScenarioParseError(format.name(), format!("{}", e)))?,
+        };
+        c.with_source(parse_value(s, format)?).validate()
+    }
+    /// Load the scenario file with the scenarios it `extends` and the parameter files it
+    /// `includes`, validate it and load the Ethereum headers
+    pub fn from_file(file_name: &str) -> Result<Self, Error> {
+        let path = Path::new(file_name);
+        let mut contents = String::new();
+        File::open(path)?.read_to_string(&mut contents)?;
+        let format = Format::from_file(file_name, &contents);
+        let value = parse_value(&contents, format)?;
+        let mut c = if value.get("extends").is_none() && value.get("includes").is_none() {
+            ScenarioConfig::from_format(&contents, format)?
+        } else {
+            let value = load_value(path, &mut Vec::new())?;
+            serde_json::from_value::<ScenarioConfig>(value.clone())
+                .map_err(|e| Error::ScenarioParseError(format.name(), format!("{}", e)))?
+                .with_source(value)
+                .validate()?
+        };
+        c.dir = path.parent().map(Path::to_path_buf);
+        c.load_ethereum_headers(path.parent().unwrap_or_else(|| Path::new(".")))?;
+        Ok(c)
+    }
+    fn with_source(self, source: serde_json::Value) -> Self {
+        ScenarioConfig {
+            source: Some(source),
+            ..self
+        }
+    }
+    fn validate(self) -> Result<Self, Error> {
+        let (c, problems) = self.resolve();
+        match problems.into_iter().next() {
+            Some(e) => Err(e),
+            None => Ok(c),
+        }
+    }
+    /// All the problems of the scenario with the field paths, instead of the first one, the
+    /// equations are checked too, though they are only loaded in simulation when used
+    pub(crate) fn problems(&self) -> Vec<Error> {
+        let equations = vec![
+            ("challenge_function", self.get_challenge_equation().err()),
+            ("target_function", self.get_sample_equation().err()),
+            ("bond_function", self.get_bond_equation().err()),
+            ("reward_function", self.get_reward_equation().err()),
+        ];
+        let mut problems: Vec<Error> = equations
+            .into_iter()
+            .filter_map(|(path, e)| e.map(|e| e.in_table(path)))
+            .collect();
+        problems.append(&mut self.clone().resolve().1);
+        problems
+    }
+    /// Resolve the participants into the relayers and the challengers by their roles, resolve the
+    /// strategies and add the Darwinia relayer, return the scenario resolved and the problems found
+    /// with the field paths, the scenario is valid if there is no problem
+    fn resolve(self) -> (Self, Vec<Error>) {
+        let mut c = self;
+        c.challenge_function.make_ascii_uppercase();
+        let mut problems = Vec::new();
+        let tables: Vec<(&str, Option<Result<(), Error>>)> = vec![
+            ("challenge_linear", c.challenge_linear.map(|w| w.validate())),
+            ("bond_linear", c.bond_linear.map(|f| f.validate())),
+            ("reward_split", c.reward_split.map(|r| r.validate())),
+            (
+                "reward_treasury_last",
+                c.reward_treasury_last.map(|r| r.validate()),
+            ),
+            ("treasury", c.treasury.map(|t| t.validate())),
+            ("transaction_cost", c.transaction_cost.map(|t| t.validate())),
+            ("block_time", c.block_time.as_ref().map(|b| b.validate())),
+            ("header", c.header.map(|h| h.validate())),
+        ];
+        for (path, r) in tables.into_iter() {
+            if let Some(Err(e)) = r {
+                problems.push(e.in_table(path));
+            }
+        }
 
-        for (i, r) in c.relayers.iter_mut().enumerate() {
-            if let Some(n) = &r.name {
-                if n.to_uppercase() == "DARWINIA".to_string() {
-                    return Err(Error::ParameterError(
-                        "Darwinia relayer alway honest, you do not need modify it",
-                    ));
+        // the field paths of the relayers and the challengers in the scenario
+        let mut relayer_paths: Vec<String> = (0..c.relayers.len())
+            .map(|i| format!("relayers[{}]", i))
+            .collect();
+        let mut challenger_paths: Vec<String> = (0..c.challengers.iter().flatten().count())
+            .map(|i| format!("challengers[{}]", i))
+            .collect();
+        for (i, p) in c
+            .participants
+            .take()
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+        {
+            let path = format!("participants[{}]", i);
+            match p.role {
+                Some(Role::Relayer) => {
+                    relayer_paths.push(path);
+                    c.relayers.push(p);
                 }
-            } else {
-                r.name = Some(format!(" {}", i));
-            };
-            // TODO: check name should be use number
-            r.choice.make_ascii_uppercase();
-            max_chose = std::cmp::max(max_chose, r.choice.len());
-            for c in r.choice.chars() {
-                if c != 'H' && c != 'L' && c != 'N' {
-                    return Err(Error::ParameterError("relayer chose must be 'H', 'L', 'N'"));
+                Some(Role::Challenger) => {
+                    challenger_paths.push(path);
+                    c.challengers.get_or_insert_with(Vec::new).push(p);
                 }
+                None => problems.push(Error::field(
+                    &format!("{}.role", path),
+                    "the role should be relayer or challenger",
+                )),
+            }
+        }
+        let mut relayers = std::mem::take(&mut c.relayers);
+        let (max_chose, mut relayer_problems) = c.resolve_relayers(&mut relayers, &relayer_paths);
+        problems.append(&mut relayer_problems);
+        c.relayers = relayers;
+        if let Some(mut challengers) = c.challengers.take() {
+            if c.relayers.len() != 1 {
+                problems.push(Error::field(
+                    "relayers",
+                    "there should be only one relayer in relayer-challenger mode",
+                ));
+            }
+            let relayer_choice = c
+                .relayers
+                .first()
+                .map(|r| r.choice.clone())
+                .unwrap_or_default();
+            problems.append(&mut c.resolve_strategies(
+                &mut challengers,
+                &challenger_paths,
+                Some(&relayer_choice),
+            ));
+            for (ch, path) in challengers.iter().zip(challenger_paths.iter()) {
+                problems.append(&mut challenger_problems(path, ch, relayer_choice.len()));
             }
+            problems.append(&mut identify(
+                &mut challengers,
+                &challenger_paths,
+                Role::Challenger,
+            ));
+            c.challengers = Some(challengers);
+        } else {
+            add_darwinia_relayer(&mut c.relayers, max_chose);
         }
-        if c.challengers.is_some() {
-            if c.relayers.len() > 1 {
-                return Err(Error::ParameterError("There is only one relayer in relayer-challenger mode or in relayer-challengers mode"));
+        if let Some(games) = &c.games {
+            if c.challengers.is_some() {
+                problems.push(Error::field(
+                    "games",
+                    "concurrent games are only supported in relayers-only mode",
+                ));
+            }
+            for (i, g) in games.iter().enumerate() {
+                let paths: Vec<String> = (0..g.relayers.len())
+                    .map(|j| format!("games[{}].relayers[{}]", i, j))
+                    .collect();
+                problems.append(&mut c.resolve_relayers(&mut g.relayers.clone(), &paths).1);
             }
-            let relayer_chose_length = c.relayers[0].clone().choice.len();
+            problems.append(&mut c.disputed_block_problems());
+        }
+        (c, problems)
+    }
+}
 
-            for (i, ch) in c.challengers.clone().unwrap().iter_mut().enumerate() {
-                if ch.choice.len() > relayer_chose_length {
-                    return Err(Error::ParameterError(
-                        "challenger chose must smaller than relayer",
-                    ));
-                }
-                if ch.name.is_none() {
-                    ch.name = Some(format!(" {}", i));
-                };
-                for (_i, c) in ch.choice.chars().enumerate() {
-                    if c != '0' && c != '1' {
-                        return Err(Error::ParameterError("challenger chose must be '0', '1'"));
+impl FromStr for ScenarioConfig {
+    type Err = Error;
+    /// Pase from the scenario file as listed in `/scenario `, the format is detected from content
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ScenarioConfig::from_format(s, Format::from_content(s))
+    }
+}
+
+/// Parse the scenario file as a generic value, such that the scenarios can be merged
+pub(crate) fn parse_value(s: &str, format: Format) -> Result<serde_json::Value, Error> {
+    Ok(match format {
+        Format::Toml => serde_json::to_value(toml::from_str::<toml::Value>(s)?)
+            .map_err(|e| Error::ScenarioParseError(format.name(), format!("{}", e)))?,
+        Format::Yaml => serde_yaml::from_str(s)?,
+        Format::Json => serde_json::from_str(s)
+            .map_err(|e| Error::ScenarioParseError(format.name(), format!("{}", e)))?,
+    })
+}
+
+/// Load the scenario file as a generic value, the scenario it `extends` is loaded first, then the
+/// parameter files it `includes` in order, and the parameters in the file override them.
+/// The paths are relative to the file, and the files being loaded are kept in `loading` to detect
+/// the cycle.
+pub(crate) fn load_value(path: &Path, loading: &mut Vec<PathBuf>) -> Result<serde_json::Value, Error> {
+    let include_error =
+        |e: std::io::Error| Error::ScenarioIncludeError(format!("{}: {}", path.display(), e));
+    let canonical = path.canonicalize().map_err(include_error)?;
+    if loading.contains(&canonical) {
+        let cycle: Vec<String> = loading
+            .iter()
+            .chain(std::iter::once(&canonical))
+            .map(|p| p.display().to_string())
+            .collect();
+        return Err(Error::ScenarioIncludeError(format!(
+            "cycle detected: {}",
+            cycle.join(" -> ")
+        )));
+    }
+    let contents = std::fs::read_to_string(path).map_err(include_error)?;
+    let mut value = parse_value(
+        &contents,
+        Format::from_file(&path.to_string_lossy(), &contents),
+    )?;
+    let table = value.as_object_mut().ok_or_else(|| {
+        Error::ScenarioIncludeError(format!("{}: should be a table", path.display()))
+    })?;
+    let dir = canonical.parent().map(Path::to_path_buf).unwrap_or_default();
+    if let Some(f) = table.get_mut("ethereum_headers") {
+        if let Some(s) = f.as_str() {
+            *f = dir.join(s).to_string_lossy().into_owned().into();
+        }
+    }
+
+    let mut parents = Vec::new();
+    match table.remove("extends") {
+        Some(serde_json::Value::String(f)) => parents.push(f),
+        None => {}
+        _ => {
+            return Err(Error::ScenarioIncludeError(format!(
+                "{}: `extends` should be a file name",
+                path.display()
+            )))
+        }
+    }
+    match table.remove("includes") {
+        Some(serde_json::Value::Array(fs)) if fs.iter().all(|f| f.is_string()) => {
+            parents.extend(fs.iter().map(|f| f.as_str().unwrap().to_string()))
+        }
+        None => {}
+        _ => {
+            return Err(Error::ScenarioIncludeError(format!(
+                "{}: `includes` should be a list of file names",
+                path.display()
+            )))
+        }
+    }
+
+    loading.push(canonical);
+    let mut merged = serde_json::Value::Object(serde_json::Map::new());
+    for f in parents {
+        merge_value(&mut merged, load_value(&dir.join(f), loading)?);
+    }
+    loading.pop();
+    merge_value(&mut merged, value);
+    Ok(merged)
+}
+
+/// Merge the tables recursively, the other values including the lists, such as `relayers`, are
+/// replaced
+fn merge_value(base: &mut serde_json::Value, value: serde_json::Value) {
+    match (base, value) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(table)) => {
+            for (k, v) in table {
+                match base.get_mut(&k) {
+                    Some(b) => merge_value(b, v),
+                    None => {
+                        base.insert(k, v);
                     }
                 }
             }
-        } else {
-            let mut relayers = vec![RelayerConfig {
-                name: Some("Darwinia".to_string()),
-                choice: "H".repeat(max_chose + 1),
-            }];
-            relayers.append(&mut c.relayers);
-            c.relayers = relayers;
         }
-        Ok(c)
+        (base, value) => *base = value,
+    }
+}
+
+/// The problems of the relayer at the field path, for example `relayers[1]`
+fn relayer_problems(path: &str, r: &RelayerConfig) -> Vec<Error> {
+    let mut problems = Vec::new();
+    for (key, v) in [("id", &r.id), ("name", &r.name)].iter() {
+        if v.as_ref().map(|n| n.to_uppercase()) == Some("DARWINIA".to_string()) {
+            problems.push(Error::field(
+                &patch::join_key(path.to_string(), key),
+                "Darwinia relayer is added and always honest, use another name",
+            ));
+        }
     }
+    problems.append(&mut choice_problems(path, &r.choice.to_uppercase(), "HLN"));
+    problems.append(&mut participant_problems(path, r));
+    problems
+}
+
+/// The problems of the challenger at the field path against the rounds of the relayer
+fn challenger_problems(path: &str, c: &ChallengerConfig, rounds: usize) -> Vec<Error> {
+    let mut problems = Vec::new();
+    if c.choice.len() > rounds {
+        problems.push(Error::FieldError(
+            patch::join_key(path.to_string(), "choice"),
+            format!(
+                "the choice is longer than the choice of relayer ({} > {})",
+                c.choice.len(),
+                rounds
+            ),
+        ));
+    }
+    problems.append(&mut choice_problems(path, &c.choice, "01"));
+    problems.append(&mut participant_problems(path, c));
+    problems
+}
+
+/// The problems of the choice, with the position of each move not allowed
+fn choice_problems(path: &str, choice: &str, allowed: &str) -> Vec<Error> {
+    let allowed_moves: Vec<String> = allowed.chars().map(|c| format!("'{}'", c)).collect();
+    choice
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| !allowed.contains(*c))
+        .map(|(i, c)| {
+            Error::FieldError(
+                patch::join_key(path.to_string(), "choice"),
+                format!(
+                    "'{}' at position {} should be {}",
+                    c,
+                    i + 1,
+                    allowed_moves.join(", ")
+                ),
+            )
+        })
+        .collect()
+}
+
+/// The problems of the latency and the transaction cost of the participant
+fn participant_problems(path: &str, p: &ParticipantConfig) -> Vec<Error> {
+    let mut problems = Vec::new();
+    if let Some(Err(e)) = p.latency.as_ref().map(|l| l.validate()) {
+        problems.push(e.in_table(&patch::join_key(path.to_string(), "latency")));
+    }
+    if let Some(Err(e)) = p.transaction_cost.map(|t| t.validate()) {
+        problems.push(e.in_table(&patch::join_key(path.to_string(), "transaction_cost")));
+    }
+    problems
+}
+
+/// Identify the participants without id by the name, or by the role and the index in the list,
+/// so the ids are the same in each run
+fn identify(participants: &mut [ParticipantConfig], paths: &[String], role: Role) -> Vec<Error> {
+    let mut ids = std::collections::HashSet::new();
+    let mut problems = Vec::new();
+    for ((i, p), path) in participants.iter_mut().enumerate().zip(paths.iter()) {
+        if p.id.is_none() {
+            p.id = Some(
+                p.name
+                    .clone()
+                    .unwrap_or_else(|| format!("{}-{}", role.name(), i)),
+            );
+        }
+        if !ids.insert(p.id().to_string()) {
+            problems.push(Error::field(
+                &patch::join_key(path.to_string(), "id"),
+                "the ids of participants should be unique",
+            ));
+        }
+    }
+    problems
+}
+
+/// The Darwinia relayer is always honest, and submit till the end of the game
+fn add_darwinia_relayer(relayers: &mut Vec<RelayerConfig>, max_chose: usize) {
+    let mut darwinia = vec![RelayerConfig {
+        id: Some("Darwinia".to_string()),
+        name: Some("Darwinia".to_string()),
+        choice: "H".repeat(max_chose + 1),
+        ..Default::default()
+    }];
+    darwinia.append(relayers);
+    *relayers = darwinia;
 }
 
 #[cfg(test)]
@@ -507,7 +1219,30 @@ mod tests {
 			choice = "HHHHHHH"
 		"#,
         );
-        assert!(format!("{:?}", c).contains("Wd should not be negative"));
+        assert_eq!(
+            format!("{}", c.unwrap_err()),
+            "Unexpected parameter `challenge_linear.Wd`: should not be negative"
+        );
+        let c = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10.0"
+			reward_function = "split"
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LL"
+
+			[[participants]]
+			role = "relayer"
+			choice = "HHX"
+		"#,
+        );
+        assert_eq!(
+            format!("{}", c.unwrap_err()),
+            "Unexpected parameter `participants[0].choice`: 'X' at position 3 should be 'H', 'L', 'N'"
+        );
     }
     #[test]
     fn test_auto_upper_case() {
@@ -547,6 +1282,189 @@ mod tests {
         let bond_function = c.get_bond_equation();
         assert!(bond_function.is_ok());
         assert_eq!(bond_function.unwrap().calculate(0), 1.2222);
+
+        c.apply_patch(vec![
+            "challenge_linear.C=9",
+            "challenge_linear.Wd=1.234",
+            "bond_linear.W=1.5",
+            "bond_linear.C=10",
+            "bond_linear.M=100",
+            "reward_split.P=0.9",
+            "F=3.5",
+            "De=500",
+            "title=What If",
+            "relayers[1].choice=llh",
+            "relayers[2].choice=HL",
+        ])
+        .unwrap();
+        assert_eq!(c.get_challenge_equation().unwrap().calculate(10, 10), 9487);
+        assert!(format!("{:?}", c.challenge_linear).contains("Wd: 1.234, We: 0.0, C: 9"));
+        assert!(format!("{:?}", c.bond_linear).contains("W: 1.5, C: 10.0, M: 100.0"));
+        assert_eq!(format!("{:?}", c.reward_split), "Some(SplitConfig { P: 0.9 })");
+        assert_eq!((c.F, c.De), (Some(3.5), Some(500)));
+        assert_eq!(c.title, Some("What If".to_string()));
+        let choices: Vec<&str> = c.relayers.iter().map(|r| r.choice.as_str()).collect();
+        assert_eq!(choices, vec!["HHHH", "LL", "LLH", "HL"]);
+
+        assert!(c.apply_patch(vec!["Dd=many"]).is_err());
+        assert!(c.apply_patch(vec!["relayers[9].choice=H"]).is_err());
+        assert!(c.apply_patch(vec!["relayers[0].choice=X"]).is_err());
+        let e = c.apply_patch(vec!["challenge_linear.X=1"]).unwrap_err();
+        assert!(format!("{}", e).contains("challenge_linear.X: unknown path"));
+        let e = c.apply_patch(vec!["bond_linear.C=ten"]).unwrap_err();
+        assert!(format!("{}", e).contains("bond_linear.C: should be a number"));
+        assert_eq!(c.De, Some(500));
+    }
+    #[test]
+    fn test_apply_patch_on_current() {
+        let mut c = <ScenarioConfig>::from_str(TOML_CONFIG).unwrap();
+        c.F = Some(3.0);
+        c.apply_patch(vec!["Dd=10"]).unwrap();
+        assert_eq!((c.F, c.Dd), (Some(3.0), Some(10)));
+        c.relayers[1].choice = "HH".to_string();
+        let e = c.apply_patch(vec!["Dd=20"]).unwrap_err();
+        assert!(format!("{}", e).contains("relayers: changed after the scenario is loaded"));
+    }
+    #[test]
+    fn test_apply_patch_ethereum_headers() {
+        let dir = std::env::temp_dir().join(format!("refit-headers-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let sample = std::fs::read_to_string(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/ethereum-headers-sample.json"),
+        )
+        .unwrap();
+        let headers: Vec<serde_json::Value> = serde_json::from_str(&sample).unwrap();
+        for (name, len) in [("a.json", 30), ("b.json", 20)].iter() {
+            let json = serde_json::to_string(&headers[..*len]).unwrap();
+            std::fs::write(dir.join(name), json).unwrap();
+        }
+        std::fs::write(
+            dir.join("scenario.toml"),
+            format!(
+                "ethereum_headers = \"a.json\"\n{}",
+                TOML_CONFIG.replace("De = 1000", "")
+            ),
+        )
+        .unwrap();
+        let mut c = ScenarioConfig::from_file(dir.join("scenario.toml").to_str().unwrap()).unwrap();
+        assert_eq!(c.De, Some(29));
+        c.apply_patch(vec!["Dd=10"]).unwrap();
+        assert_eq!(c.canonical_headers.as_ref().map(Vec::len), Some(30));
+        c.apply_patch(vec!["ethereum_headers=b.json"]).unwrap();
+        assert_eq!(c.canonical_headers.as_ref().map(Vec::len), Some(20));
+        assert_eq!(c.De, Some(19));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+    #[test]
+    fn test_set_ethereum_headers() {
+        let mut headers = vec![Header::new(1000, 0, 4096, 100, String::new())];
+        for i in 1..=10 {
+            let p = &headers[i - 1];
+            headers.push(Header::new(
+                1000 + i,
+                p.hash,
+                4096,
+                p.timestamp + 12,
+                String::new(),
+            ));
+        }
+        let mut c = <ScenarioConfig>::from_str(TOML_CONFIG).unwrap();
+        assert!(c.set_ethereum_headers(Vec::new()).is_err());
+        assert!(c.set_ethereum_headers(headers.clone()).is_err());
+        c.De = None;
+        c.set_ethereum_headers(headers.clone()).unwrap();
+        assert_eq!(c.De, Some(10));
+        assert_eq!(c.F, Some(2.0));
+        assert_eq!(c.ethereum_genesis(), 1000);
+        assert_eq!(c.disputed_block(), 1005);
+
+        // the concurrent games are validated with the disputed blocks from the genesis
+        let mut c = <ScenarioConfig>::from_str(&format!(
+            "{}\n[[games]]\nDe = 8\n[[games.relayers]]\nchoice = \"H\"\n",
+            TOML_CONFIG.replace("De = 1000", "De = 10")
+        ))
+        .unwrap();
+        c.set_ethereum_headers(headers.clone()).unwrap();
+        assert_eq!(c.disputed_block(), 1005);
+        c.games.as_mut().unwrap()[0].De = Some(10);
+        assert!(c.set_ethereum_headers(headers).is_err());
+    }
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(Format::from_file("basic.toml", "title: x"), Format::Toml);
+        assert_eq!(Format::from_file("basic.yml", TOML_CONFIG), Format::Toml);
+        assert_eq!(Format::from_file("basic.yml", "title: x"), Format::Yaml);
+        assert_eq!(
+            Format::from_file("basic", r#"{"title": "x"}"#),
+            Format::Json
+        );
+        let e = ScenarioConfig::from_format("title = ", Format::Toml).unwrap_err();
+        assert!(format!("{}", e).contains("parsed as TOML"));
+        let e = ScenarioConfig::from_format(r#"{"title": }"#, Format::Json).unwrap_err();
+        assert!(format!("{}", e).contains("parsed as JSON"));
+    }
+    #[test]
+    fn test_load_scenarios_in_all_formats() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../scenario");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.is_file() {
+                continue;
+            }
+            ScenarioConfig::from_file(path.to_str().unwrap()).unwrap();
+            let value = toml::Value::try_from(load_value(&path, &mut Vec::new()).unwrap()).unwrap();
+            let toml_str = toml::to_string(&value).unwrap();
+            let yaml_str = serde_yaml::to_string(&value).unwrap();
+            let json_str = serde_json::to_string(&value).unwrap();
+
+            let c = ScenarioConfig::from_format(&toml_str, Format::Toml).unwrap();
+            let yaml_c = ScenarioConfig::from_format(&yaml_str, Format::Yaml).unwrap();
+            let json_c = <ScenarioConfig>::from_str(&json_str).unwrap();
+            assert_eq!(format!("{:?}", c), format!("{:?}", yaml_c), "{:?}", path);
+            assert_eq!(format!("{:?}", c), format!("{:?}", json_c), "{:?}", path);
+        }
+    }
+    #[test]
+    fn test_extends_and_includes() {
+        let dir = std::env::temp_dir().join(format!("refit-extends-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("common")).unwrap();
+        std::fs::write(dir.join("base.toml"), TOML_CONFIG).unwrap();
+        std::fs::write(dir.join("common/split.yml"), "reward_split:\n  P: 0.7\n").unwrap();
+        std::fs::write(
+            dir.join("child.toml"),
+            r#"
+			extends = "base.toml"
+			includes = ["common/split.yml"]
+			De = 500
+			[challenge_linear]
+			C = 3
+			[[relayers]]
+			name = "Evil"
+			choice = "LLL"
+			"#,
+        )
+        .unwrap();
+        std::fs::write(dir.join("a.toml"), r#"extends = "b.toml""#).unwrap();
+        std::fs::write(dir.join("b.toml"), r#"includes = ["a.toml"]"#).unwrap();
+        std::fs::write(dir.join("missing.toml"), r#"extends = "none.toml""#).unwrap();
+
+        let c = ScenarioConfig::from_file(dir.join("child.toml").to_str().unwrap()).unwrap();
+        assert_eq!(c.Dd, Some(100));
+        assert_eq!(c.De, Some(500));
+        assert_eq!(
+            format!("{:?}", c.reward_split.unwrap()),
+            "SplitConfig { P: 0.7 }"
+        );
+        let challenge_linear = format!("{:?}", c.challenge_linear.unwrap());
+        assert!(challenge_linear.contains("C: 3, Md: 100"));
+        assert_eq!(c.relayers.len(), 2);
+        assert_eq!(c.relayers[1].choice, "LLL");
+
+        let e = ScenarioConfig::from_file(dir.join("a.toml").to_str().unwrap()).unwrap_err();
+        assert!(format!("{}", e).contains("cycle detected"));
+        let e = ScenarioConfig::from_file(dir.join("missing.toml").to_str().unwrap()).unwrap_err();
+        assert!(format!("{}", e).contains("none.toml"));
+        std::fs::remove_dir_all(dir).unwrap();
     }
     #[test]
     fn test_plot_relay_position() {
@@ -658,4 +1576,141 @@ mod tests {
         );
         assert_eq!(c.is_ok(), true);
     }
+    #[test]
+    fn test_participants() {
+        let scenario = r#"
+			challenge_function = "linear"
+			target_function = "half"
+			bond_function = "linear"
+			reward_function = "split"
+
+			[challenge_linear]
+			Wd = 0.0
+			We = 0.0
+			C  = 1
+			Md = 100
+			Me = 100
+
+			[bond_linear]
+			W = 1.5
+			C  = 10.0
+			M = 100.0
+
+			[[relayers]]
+			choice = "LLHH"
+
+			[[participants]]
+			role = "relayer"
+			balance = 100.0
+			strategy = { type = "adaptive", X = 13.0 }
+
+			[[participants]]
+			name = "Gambler"
+			role = "relayer"
+			strategy = { type = "probabilistic", P = 1.0, rounds = 2 }
+		"#;
+        let c = <ScenarioConfig>::from_str(scenario).unwrap();
+        let relayers: Vec<(&str, &str)> = c
+            .relayers
+            .iter()
+            .map(|r| (r.id(), r.choice.as_str()))
+            .collect();
+        assert_eq!(
+            relayers,
+            vec![
+                ("Darwinia", "HHHHH"),
+                ("relayer-0", "LLHH"),
+                ("relayer-1", "HHHH"),
+                ("Gambler", "LL")
+            ]
+        );
+        assert_eq!(c.relayers[2].balance, Some(100.0));
+
+        // the ids and the random choices are the same in each run
+        let patches = vec![
+            "participants[1].strategy.P=0.5",
+            "participants[1].strategy.rounds=20",
+        ];
+        let mut c = <ScenarioConfig>::from_str(&format!("seed = 3\n{}", scenario)).unwrap();
+        c.apply_patch(patches.clone()).unwrap();
+        let mut c2 = <ScenarioConfig>::from_str(&format!("seed = 3\n{}", scenario)).unwrap();
+        c2.apply_patch(patches).unwrap();
+        assert_eq!(c.relayers[3].choice, c2.relayers[3].choice);
+        assert!(c.relayers[3].choice.contains('L') && c.relayers[3].choice.contains('H'));
+
+        let c = <ScenarioConfig>::from_str(&scenario.replace(
+            r#"[[participants]]
+			role = "relayer"
+			balance"#,
+            r#"[[participants]]
+			role = "challenger"
+			balance"#,
+        ));
+        assert!(c.is_err());
+
+        let c = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "linear"
+			target_function = "half"
+			bond_function = "linear"
+			reward_function = "split"
+
+			[bond_linear]
+			W = 1.5
+			C  = 10.0
+			M = 100.0
+
+			[[participants]]
+			name = "Evil"
+			role = "relayer"
+			choice = "LHLL"
+
+			[[participants]]
+			role = "challenger"
+			strategy = { type = "adaptive", X = 13.0 }
+
+			[[participants]]
+			role = "challenger"
+			strategy = { type = "probabilistic", P = 0.0 }
+		"#,
+        )
+        .unwrap();
+        let challengers: Vec<(&str, &str)> = c
+            .challengers
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|r| (r.id(), r.choice.as_str()))
+            .collect();
+        assert_eq!(
+            challengers,
+            vec![("challenger-0", "0100"), ("challenger-1", "0100")]
+        );
+
+        for wrong in &[
+            r#"[[participants]]
+			choice = "L""#,
+            r#"[[participants]]
+			role = "relayer"
+			choice = "L"
+			strategy = { type = "scripted", choice = "L" }"#,
+            r#"[[participants]]
+			role = "relayer"
+			id = "relayer-0"
+			choice = "L""#,
+            r#"[[participants]]
+			role = "relayer"
+			strategy = { type = "probabilistic", P = 1.5 }"#,
+            r#"[[participants]]
+			role = "relayer"
+			choice = "L"
+			latency = { model = "fixed" }"#,
+        ] {
+            assert!(
+                <ScenarioConfig>::from_str(&format!("{}\n{}", scenario, wrong)).is_err(),
+                "{}",
+                wrong
+            );
+        }
+    }
 }
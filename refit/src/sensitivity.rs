@@ -0,0 +1,337 @@
+//! Sensitivity module
+//! Perturb each numeric parameter of the scenario by ±δ, such as `Dd`, `De`, `F`, the parameters
+//! of the challenge, bond, reward and treasury equations in use, and rank the parameters by the effect on
+//! the duration, the max bond pool and the balance of each participant.
+//!
+//! The parameter is perturbed relatively, `C = 10.0` is perturbed into `9.0` and `11.0` with δ 0.1,
+//! and the zero is perturbed into `-δ` and `δ`.  The integer parameter is rounded and at least
+//! moved by 1.  The perturbation failing the validation of the scenario is reported as invalid.
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::error::Error;
+use crate::game::run;
+use crate::scenario::ScenarioConfig;
+
+/// The width of each side of the tornado bar
+static BAR_WIDTH: usize = 20;
+
+/// # Outcome
+/// The metrics of the game simulated
+#[derive(Debug, Clone, Default)]
+pub struct Outcome {
+    /// The Darwinia blocks of the game
+    pub duration: f64,
+    /// The max value of the bond pool
+    pub max_bond: f64,
+    /// The balance earned by each participant, keyed by id
+    pub balances: BTreeMap<String, f64>,
+}
+
+impl Outcome {
+    /// Play the game of the scenario and measure the outcome
+    pub fn simulate(config: &ScenarioConfig) -> Result<Self, Error> {
+        let (chains_status, result) = run(config)?;
+        Ok(Outcome {
+            duration: result.duration as f64,
+            max_bond: result.max_bond_value,
+            balances: chains_status
+                .relayers
+                .iter()
+                .chain(chains_status.challengers.iter())
+                .map(|(id, s)| (id.clone(), s.balance() - s.initial_balance))
+                .collect(),
+        })
+    }
+    /// The metrics as (name, value)
+    fn metrics(&self) -> Vec<(String, f64)> {
+        let mut metrics = vec![
+            ("Duration".to_string(), self.duration),
+            ("Max Bond Value".to_string(), self.max_bond),
+        ];
+        for (id, b) in self.balances.iter() {
+            metrics.push((format!("Balance of {}", id), *b));
+        }
+        metrics
+    }
+}
+
+/// # Perturbation
+/// The outcomes of a parameter perturbed down and up, `None` if the value is not valid
+#[derive(Debug)]
+pub struct Perturbation {
+    /// The field path of the parameter
+    pub path: String,
+    pub value: f64,
+    pub low: (f64, Option<Outcome>),
+    pub high: (f64, Option<Outcome>),
+}
+
+impl Perturbation {
+    /// The outcomes of the metric perturbed down and up
+    fn effect(&self, metric: usize) -> (Option<f64>, Option<f64>) {
+        let get = |o: &Option<Outcome>| o.as_ref().map(|o| o.metrics()[metric].1);
+        (get(&self.low.1), get(&self.high.1))
+    }
+}
+
+/// # Sensitivity
+/// The perturbations of all the numeric parameters
+#[derive(Debug)]
+pub struct Sensitivity {
+    pub delta: f64,
+    pub base: Outcome,
+    pub perturbations: Vec<Perturbation>,
+}
+
+impl Sensitivity {
+    /// The perturbations ranked by the swing of the metric, the difference between the outcomes
+    /// perturbed down and up, the invalid side is deemed as the base outcome
+    pub fn tornado(&self, metric: usize) -> Vec<(&Perturbation, f64, f64)> {
+        let base = self.base.metrics()[metric].1;
+        let mut rows: Vec<(&Perturbation, f64, f64)> = self
+            .perturbations
+            .iter()
+            .map(|p| {
+                let (low, high) = p.effect(metric);
+                (p, low.unwrap_or(base) - base, high.unwrap_or(base) - base)
+            })
+            .collect();
+        rows.sort_by(|a, b| {
+            let swing = |r: &(&Perturbation, f64, f64)| (r.2 - r.1).abs();
+            swing(b).partial_cmp(&swing(a)).unwrap()
+        });
+        rows
+    }
+}
+
+fn fmt_change(change: Option<f64>) -> String {
+    match change {
+        Some(c) if c > 0.0 => format!("+{}", c),
+        Some(c) => format!("{}", c),
+        None => "invalid".to_string(),
+    }
+}
+
+impl fmt::Display for Sensitivity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Parameters: {}, Perturbation: ±{}",
+            self.perturbations.len(),
+            self.delta
+        )?;
+        for (m, (name, base)) in self.base.metrics().into_iter().enumerate() {
+            write!(f, "\n\n{} (base {})", name, base)?;
+            let rows = self.tornado(m);
+            let max = rows
+                .iter()
+                .map(|r| r.1.abs().max(r.2.abs()))
+                .fold(0.0, f64::max);
+            for (p, low, high) in rows {
+                let width = |c: f64| {
+                    if max > 0.0 {
+                        (c.abs() / max * BAR_WIDTH as f64).round() as usize
+                    } else {
+                        0
+                    }
+                };
+                let left = width(low.min(0.0)).max(width(high.min(0.0)));
+                let right = width(low.max(0.0)).max(width(high.max(0.0)));
+                let (l, h) = p.effect(m);
+                write!(
+                    f,
+                    "\n{:>BAR_WIDTH$}|{:<BAR_WIDTH$} {} = {}: {} -> {}, {} -> {}",
+                    "-".repeat(left),
+                    "+".repeat(right),
+                    p.path,
+                    p.value,
+                    p.low.0,
+                    fmt_change(l.map(|_| low)),
+                    p.high.0,
+                    fmt_change(h.map(|_| high)),
+                    BAR_WIDTH = BAR_WIDTH
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The numeric parameters of the scenario resolved as (path, value, integer), the numbers at the
+/// top level with their defaults and in the tables of the equations in use, except the seeds and
+/// the lists such as relayers
+pub fn numeric_parameters(config: &ScenarioConfig) -> Vec<(String, f64, bool)> {
+    let mut value = serde_json::to_value(config).unwrap_or_default();
+    if let Some(table) = value.as_object_mut() {
+        let defaults = [
+            ("Dd", Value::from(0)),
+            ("De", Value::from(100)),
+            ("F", Value::from(2.0)),
+        ];
+        for (k, v) in defaults.iter() {
+            if table.get(*k).and_then(Value::as_f64).is_none() {
+                table.insert(k.to_string(), v.clone());
+            }
+        }
+        // F is not used with the block time
+        if config.block_time.is_some() {
+            table.remove("F");
+        }
+    }
+    let in_use = |table: &str| {
+        let (function, name) = match table {
+            "challenge_linear" => (&config.challenge_function, "linear"),
+            "bond_linear" => (&config.bond_function, "linear"),
+            "reward_split" => (&config.reward_function, "split"),
+            "reward_treasury_last" => (&config.reward_function, "treasury_last"),
+            _ => return true,
+        };
+        function.eq_ignore_ascii_case(name)
+    };
+    let mut parameters = Vec::new();
+    if let Some(table) = value.as_object() {
+        for (k, v) in table.iter().filter(|(k, _)| in_use(k)) {
+            collect_numbers(k.clone(), v, &mut parameters);
+        }
+    }
+    parameters
+}
+
+/// Collect the numbers in the value and the tables in it, except the seeds and the lists
+fn collect_numbers(path: String, v: &Value, parameters: &mut Vec<(String, f64, bool)>) {
+    if path.rsplit('.').next() == Some("seed") {
+        return;
+    }
+    if let Some(n) = v.as_f64() {
+        parameters.push((path, n, v.is_u64() || v.is_i64()));
+    } else if let Some(t) = v.as_object() {
+        for (key, v) in t.iter() {
+            collect_numbers(format!("{}.{}", path, key), v, parameters);
+        }
+    }
+}
+
+/// Apply the value on the parameter and simulate
+fn perturb(config: &ScenarioConfig, path: &str, value: f64) -> (f64, Option<Outcome>) {
+    let mut c = config.clone();
+    let outcome = c
+        .apply_patch(vec![&format!("{}={}", path, value)])
+        .and_then(|_| Outcome::simulate(&c))
+        .ok();
+    (value, outcome)
+}
+
+/// Perturb each numeric parameter of the scenario by ±delta, and simulate the outcomes
+pub fn analyse(config: &ScenarioConfig, delta: f64) -> Result<Sensitivity, Error> {
+    if config.games.is_some() {
+        return Err(Error::ParameterError(
+            "Concurrent games can not be analysed",
+        ));
+    }
+    if delta <= 0.0 {
+        return Err(Error::ParameterError("delta should be positive"));
+    }
+    if config.source.is_none() {
+        return Err(Error::ParameterError(
+            "the scenario is not loaded from a file",
+        ));
+    }
+    let base = Outcome::simulate(config)?;
+    let mut perturbations = Vec::new();
+    for (path, value, integer) in numeric_parameters(config) {
+        let (low, high) = if value == 0.0 {
+            (-delta, delta)
+        } else {
+            (value * (1.0 - delta), value * (1.0 + delta))
+        };
+        let (low, high) = if integer {
+            (low.round().min(value - 1.0), high.round().max(value + 1.0))
+        } else {
+            // drop the floating point noise, such as 1.6500000000000001
+            let round = |v: f64| (v * 1e9).round() / 1e9;
+            (round(low), round(high))
+        };
+        let (low, high) = (perturb(config, &path, low), perturb(config, &path, high));
+        perturbations.push(Perturbation {
+            path,
+            value,
+            low,
+            high,
+        });
+    }
+    Ok(Sensitivity {
+        delta,
+        base,
+        perturbations,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+    #[test]
+    fn test_analyse() {
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "linear"
+			reward_function = "split"
+			seed = 3
+
+			[bond_linear]
+			W = 1.0
+			C = 10.0
+			M = 100.0
+
+			[reward_split]
+			P = 1.0
+
+			[reward_treasury_last]
+			C = 100.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LL"
+
+			[[relayers]]
+			name = "Honest"
+			choice = "HHH"
+			"#,
+        )
+        .unwrap();
+        let s = analyse(&config, 0.1).unwrap();
+        let paths: Vec<&str> = s.perturbations.iter().map(|p| p.path.as_str()).collect();
+        // the defaults are perturbed, and the treasury_last reward is not in use
+        assert_eq!(
+            paths,
+            vec![
+                "Dd",
+                "De",
+                "F",
+                "bond_linear.C",
+                "bond_linear.M",
+                "bond_linear.W",
+                "reward_split.P"
+            ]
+        );
+        let de = &s.perturbations[1];
+        assert_eq!((de.value, de.low.0, de.high.0), (100.0, 90.0, 110.0));
+        let c = &s.perturbations[3];
+        assert_eq!((c.low.0, c.high.0), (9.0, 11.0));
+
+        // the bond of the first round is the most sensitive for the max bond pool
+        let tornado = s.tornado(1);
+        assert_eq!(tornado[0].0.path, "bond_linear.C");
+        // P can not be more than 1
+        let p = &s.perturbations[6];
+        assert!(p.low.1.is_some());
+        assert!(p.high.1.is_none());
+
+        assert!(analyse(&config, 0.0).is_err());
+    }
+}
@@ -0,0 +1,499 @@
+//! Step module
+//! Play a game round by round in the terminal, and show the chain status, the relay positions,
+//! the bond pool and the balance of each participant after each round.
+//!
+//! The commands are read line by line from a prompt, and the view is redrawn in place if the
+//! output is a terminal.
+//!
+//! The next move of a participant can be changed on the fly, which forks the game from the round,
+//! and the game can be stepped backward.  The forks are kept with their rounds and applied on the
+//! scenario, and the forks made after the round stepped back to are dropped, so the moves before
+//! the forks are the moves in the scenario.  The chains status and the game after each round are
+//! cached, so stepping forward and backward plays no round again, and the game is replayed from
+//! the genesis only when the forks change, so the forks and the steps backward never diverge from
+//! the game played by `refit <scenario>`.
+use std::fmt::Write as _;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+use colored::Colorize;
+
+use crate::chain::ChainsStatus;
+use crate::error::Error;
+use crate::game::Game;
+use crate::scenario::{ParticipantConfig, ScenarioConfig};
+use crate::timeline::{self, Timeline};
+
+/// Clear the terminal and move the cursor to the top left
+static CLEAR: &str = "\x1b[2J\x1b[H";
+
+static HELP: &str = "Commands:
+	n, <Enter>        play the next round
+	b                 step back a round
+	m <id> <choice>   change the next move of the participant, H, L or N for a relayer, 0 or 1 for a challenger
+	e                 play to the end of the game
+	h                 show this help
+	q                 quit";
+
+/// # Command
+/// The command input in each step
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Next,
+    Back,
+    /// Change the next move of the participant
+    Move(String, char),
+    End,
+    Help,
+    Quit,
+}
+
+impl FromStr for Command {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = s.split_whitespace().collect();
+        match words.as_slice() {
+            [] | ["n"] => Ok(Command::Next),
+            ["b"] => Ok(Command::Back),
+            ["e"] => Ok(Command::End),
+            ["h"] | ["?"] => Ok(Command::Help),
+            ["q"] => Ok(Command::Quit),
+            ["m", id, choice] if choice.chars().count() == 1 => Ok(Command::Move(
+                id.to_string(),
+                choice.chars().next().unwrap().to_ascii_uppercase(),
+            )),
+            _ => Err(Error::CliError(format!(
+                "unknown command `{}`, input h for help",
+                s
+            ))),
+        }
+    }
+}
+
+/// # Stepper
+/// The game played to a round, with the moves forked
+pub struct Stepper {
+    /// The scenario without the forks
+    config: ScenarioConfig,
+    /// The rounds played
+    pub round: usize,
+    /// The moves changed as (round, participant, choice), in the order of the rounds
+    pub forks: Vec<(usize, String, char)>,
+    /// The chains status and the game after each round of the forked game, from the genesis
+    snapshots: Vec<(ChainsStatus, Game)>,
+    /// The rounds played when the forked game closed
+    closed: Option<usize>,
+}
+
+/// The moves of the participant in the scenario
+fn moves_of(config: &ScenarioConfig, id: &str) -> Vec<char> {
+    config
+        .relayers
+        .iter()
+        .chain(config.challengers.iter().flatten())
+        .find(|p| p.id() == id)
+        .map(|p| p.choice.chars().collect())
+        .unwrap_or_default()
+}
+
+impl Stepper {
+    pub fn new(config: ScenarioConfig) -> Result<Self, Error> {
+        if config.games.is_some() {
+            return Err(Error::ParameterError("Concurrent games can not be stepped"));
+        }
+        Ok(Stepper {
+            config,
+            round: 0,
+            forks: Vec::new(),
+            snapshots: Vec::new(),
+            closed: None,
+        })
+    }
+
+    /// The scenario with the moves forked, the relayer ended early is padded with `N` in the
+    /// rounds before, which is submitted as honest as in the scenario data
+    fn forked(&self) -> Result<ScenarioConfig, Error> {
+        let mut config = self.config.clone();
+        for (round, id, choice) in self.forks.iter() {
+            let mut moves = moves_of(&config, id);
+            if moves.len() < *round {
+                moves.resize(*round, 'N');
+            }
+            moves[round - 1] = *choice;
+            let moves: String = moves.into_iter().collect();
+            config.set_choice(id, &moves)?;
+        }
+        Ok(config)
+    }
+
+    /// The chains status and the game after the rounds, the rounds not cached are played from the
+    /// last round cached, return `None` if the game is closed before the rounds
+    fn snapshot(&mut self, rounds: usize) -> Result<Option<&(ChainsStatus, Game)>, Error> {
+        if self.snapshots.is_empty() {
+            let config = self.forked()?;
+            let chains_status: ChainsStatus = config.clone().into();
+            let game = Game::new(&config, &chains_status)?;
+            self.snapshots.push((chains_status, game));
+        }
+        while self.snapshots.len() <= rounds && self.closed.is_none() {
+            let (mut chains_status, mut game) = self.snapshots[self.snapshots.len() - 1].clone();
+            if game.step(&mut chains_status, false) {
+                self.snapshots.push((chains_status, game));
+            } else {
+                self.closed = Some(self.snapshots.len() - 1);
+            }
+        }
+        Ok(self.snapshots.get(rounds))
+    }
+
+    /// Drop the rounds cached after the forks changed
+    fn invalidate(&mut self) {
+        self.snapshots.clear();
+        self.closed = None;
+    }
+
+    /// The game is closed after the rounds played
+    pub fn is_closed(&mut self) -> Result<bool, Error> {
+        Ok(self.snapshot(self.round + 1)?.is_none())
+    }
+
+    /// Play the next round, return false if the game is closed
+    pub fn next(&mut self) -> Result<bool, Error> {
+        if self.is_closed()? {
+            return Ok(false);
+        }
+        self.round += 1;
+        Ok(true)
+    }
+
+    /// Step back a round, return false if the game is not started.  The forks of the rounds after
+    /// the next round are dropped, and the next move forked is kept.
+    pub fn back(&mut self) -> bool {
+        if self.round == 0 {
+            return false;
+        }
+        self.round -= 1;
+        let next = self.round + 1;
+        let forks = self.forks.len();
+        self.forks.retain(|f| f.0 <= next);
+        if self.forks.len() < forks {
+            self.invalidate();
+        }
+        true
+    }
+
+    /// Change the next move of the participant, the move forked before in the round is replaced
+    pub fn fork(&mut self, id: &str, choice: char) -> Result<(), Error> {
+        let round = self.round;
+        let is = |p: &&ParticipantConfig| p.id() == id || p.name.as_deref() == Some(id);
+        let (participant, relayer) = match self.config.relayers.iter().find(is) {
+            Some(p) => (p, true),
+            None => match self.config.challengers.iter().flatten().find(is) {
+                Some(p) => (p, false),
+                None => {
+                    return Err(Error::CliError(format!(
+                        "the participant {} is not in the scenario",
+                        id
+                    )))
+                }
+            },
+        };
+        if participant.strategy.is_some() {
+            return Err(Error::CliError(format!(
+                "the moves of {} are decided by the strategy",
+                id
+            )));
+        }
+        let choices: &[char] = if relayer {
+            &['H', 'L', 'N']
+        } else {
+            &['0', '1']
+        };
+        if !choices.contains(&choice) {
+            return Err(Error::CliError(format!(
+                "the move of {} should be one of {:?}",
+                id, choices
+            )));
+        }
+        if !relayer && participant.choice.chars().count() <= round {
+            return Err(Error::CliError(format!(
+                "the challenges of {} ended before round {}",
+                id,
+                round + 1
+            )));
+        }
+        let id = participant.id().to_string();
+        self.forks.retain(|f| f.0 != round + 1 || f.1 != id);
+        self.forks.push((round + 1, id, choice));
+        self.invalidate();
+        Ok(())
+    }
+
+    /// The next move of each participant
+    fn next_moves(&self) -> Result<Vec<String>, Error> {
+        let config = self.forked()?;
+        Ok(config
+            .relayers
+            .iter()
+            .chain(config.challengers.iter().flatten())
+            .map(|p| {
+                let name = p.name.as_deref().unwrap_or_else(|| p.id());
+                match (&p.strategy, p.choice.chars().nth(self.round)) {
+                    (Some(_), _) => format!("{} by strategy", name),
+                    (None, Some(c)) => format!("{} {}", name, c),
+                    (None, None) => format!("{} -", name),
+                }
+            })
+            .collect())
+    }
+
+    /// Show the game after the rounds played in the width
+    pub fn view(&mut self, width: usize) -> Result<String, Error> {
+        let closed = self.is_closed()?;
+        let (mut chains_status, game) = match self.snapshot(self.round)? {
+            Some(s) => s.clone(),
+            None => return Err(Error::UnknownError("the round is not played")),
+        };
+        let config = self.forked()?;
+        let mut output = String::new();
+        let head = if closed {
+            format!("Round {} (closed)", self.round)
+        } else {
+            format!("Round {}", self.round)
+        };
+        writeln!(output, "{}", head.white()).unwrap();
+        let (timeline, max_bond_value) = if closed {
+            let result = game.finish(&mut chains_status);
+            (
+                Timeline::new(&config, &result, &chains_status),
+                result.max_bond_value,
+            )
+        } else {
+            let result = game.result();
+            let timeline = Timeline::in_progress(&config, result, &chains_status);
+            (timeline, 0.0)
+        };
+        write!(output, "{}", chains_status.fmt_status().cyan()).unwrap();
+        writeln!(output, "{}", timeline.render(width)).unwrap();
+        writeln!(output, "Bond Pool: {}", chains_status.submit_bond_pool).unwrap();
+        write!(output, "Balances:").unwrap();
+        for p in config
+            .relayers
+            .iter()
+            .chain(config.challengers.iter().flatten())
+        {
+            let s = match chains_status
+                .relayers
+                .get(p.id())
+                .or_else(|| chains_status.challengers.get(p.id()))
+            {
+                Some(s) => s,
+                None => continue,
+            };
+            write!(
+                output,
+                "\n\t{}: {} (bond {}, reward {})",
+                s.name.as_deref().unwrap_or(&s.id),
+                s.balance() - s.initial_balance,
+                s.pay,
+                s.reward.0 + s.reward.1
+            )
+            .unwrap();
+        }
+        if closed {
+            write!(
+                output,
+                "\n{}",
+                chains_status.fmt_relayers_bar_chart(max_bond_value)
+            )
+            .unwrap();
+        } else {
+            write!(output, "\nNext Moves: {}", self.next_moves()?.join(", ")).unwrap();
+        }
+        if !self.forks.is_empty() {
+            let forks: Vec<String> = self
+                .forks
+                .iter()
+                .map(|(r, id, c)| format!("{} {} in round {}", id, c, r))
+                .collect();
+            write!(output, "\nForks: {}", forks.join(", ")).unwrap();
+        }
+        Ok(output)
+    }
+}
+
+/// Step through the game with the commands read line by line from the input, and show the game in
+/// the output, the screen is cleared before showing the game if `redraw`
+pub fn interact<R: BufRead, W: Write>(
+    config: ScenarioConfig,
+    mut input: R,
+    mut output: W,
+    redraw: bool,
+) -> Result<(), Error> {
+    let mut stepper = Stepper::new(config)?;
+    let width = timeline::terminal_width();
+    let clear = if redraw { CLEAR } else { "" };
+    writeln!(output, "{}{}", clear, HELP)?;
+    writeln!(output, "{}", stepper.view(width)?)?;
+    loop {
+        write!(output, "> ")?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+        match line.trim().parse::<Command>() {
+            Ok(Command::Quit) => break,
+            Ok(Command::Next) => {
+                if !stepper.next()? {
+                    writeln!(output, "The game is closed")?;
+                    continue;
+                }
+            }
+            Ok(Command::Back) => {
+                if !stepper.back() {
+                    writeln!(output, "The game is not started")?;
+                    continue;
+                }
+            }
+            Ok(Command::End) => while stepper.next()? {},
+            Ok(Command::Move(id, choice)) => {
+                if let Err(e) = stepper.fork(&id, choice) {
+                    writeln!(output, "{}", e)?;
+                    continue;
+                }
+            }
+            Ok(Command::Help) => {
+                writeln!(output, "{}", HELP)?;
+                continue;
+            }
+            Err(e) => {
+                writeln!(output, "{}", e)?;
+                continue;
+            }
+        }
+        write!(output, "{}", clear)?;
+        writeln!(output, "{}", stepper.view(width)?)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::run;
+    static SCENARIO: &str = r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10"
+			reward_function = "split"
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LLL"
+			"#;
+    #[test]
+    fn test_command() {
+        assert_eq!("".parse::<Command>().unwrap(), Command::Next);
+        assert_eq!(
+            "m Evil h".parse::<Command>().unwrap(),
+            Command::Move("Evil".to_string(), 'H')
+        );
+        assert!("m Evil HL".parse::<Command>().is_err());
+        assert!("x".parse::<Command>().is_err());
+    }
+    #[test]
+    fn test_stepper() {
+        let config = <ScenarioConfig>::from_str(SCENARIO).unwrap();
+        let mut stepper = Stepper::new(config.clone()).unwrap();
+        assert!(!stepper.back());
+        assert!(stepper.next().unwrap());
+        assert!(stepper.next().unwrap());
+        assert!(stepper.back());
+        assert_eq!(stepper.round, 1);
+        // the rounds played are cached, and the next round is played to know the game is closed
+        assert_eq!(stepper.snapshots.len(), 3);
+
+        // Evil turns honest in round 2
+        stepper.fork("Evil", 'H').unwrap();
+        assert!(stepper.snapshots.is_empty());
+        assert!(stepper.fork("Evil", 'X').is_err());
+        assert!(stepper.fork("Nobody", 'H').is_err());
+        while stepper.next().unwrap() {}
+        let round = stepper.round;
+        let forked = stepper.snapshot(round).unwrap().unwrap().1.result().clone();
+        let (_, result) = run(&config).unwrap();
+        let evil = |submissions: &Vec<(String, bool)>| {
+            submissions.iter().find(|(id, _)| id == "Evil").unwrap().1
+        };
+        assert!(evil(&result.rounds[1].submissions));
+        assert!(!evil(&forked.rounds[1].submissions));
+        assert!(evil(&forked.rounds[2].submissions));
+        assert!(stepper
+            .view(80)
+            .unwrap()
+            .contains("Forks: Evil H in round 2"));
+
+        // the steps are the same with the game played at once
+        let mut stepper = Stepper::new(config.clone()).unwrap();
+        while stepper.next().unwrap() {}
+        assert_eq!(stepper.round, result.rounds.len());
+    }
+    #[test]
+    fn test_interact() {
+        let config = <ScenarioConfig>::from_str(SCENARIO).unwrap();
+        let mut output = Vec::new();
+        interact(
+            config,
+            "n\nb\nb\nm Evil Q\ne\nn\nq\n".as_bytes(),
+            &mut output,
+            false,
+        )
+        .unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Next Moves: Darwinia H, Evil L"));
+        assert!(output.contains("The game is not started"));
+        assert!(output.contains("the move of Evil should be one of"));
+        assert!(output.contains("(closed)"));
+        assert!(output.contains("The game is closed"));
+    }
+    #[test]
+    fn test_step_back_before_fork() {
+        let config = <ScenarioConfig>::from_str(SCENARIO).unwrap();
+        let (_, result) = run(&config).unwrap();
+        let mut stepper = Stepper::new(config).unwrap();
+        stepper.next().unwrap();
+        stepper.fork("Evil", 'H').unwrap();
+        stepper.fork("Evil", 'N').unwrap();
+        assert_eq!(stepper.forks, vec![(2, "Evil".to_string(), 'N')]);
+        // the lie of Evil in round 2, `N` is submitted as honest
+        let lie = |stepper: &mut Stepper| {
+            let (_, game) = stepper.snapshot(2).unwrap().unwrap();
+            game.result().rounds[1]
+                .submissions
+                .iter()
+                .find(|(id, _)| id == "Evil")
+                .map(|s| s.1)
+        };
+        while stepper.next().unwrap() {}
+        assert_eq!(lie(&mut stepper), Some(false));
+
+        // the fork is kept as the next move when stepping back to its round
+        while stepper.round > 1 {
+            stepper.back();
+        }
+        assert_eq!(stepper.forks.len(), 1);
+        assert!(stepper.view(80).unwrap().contains("Evil N"));
+
+        // the game before the fork is the game in the scenario
+        stepper.back();
+        assert!(stepper.forks.is_empty());
+        assert!(stepper.view(80).unwrap().contains("Evil L"));
+        assert_eq!(lie(&mut stepper), Some(true));
+        while stepper.next().unwrap() {}
+        assert_eq!(stepper.round, result.rounds.len());
+    }
+}
@@ -0,0 +1,306 @@
+//! Strategy module
+//! The participants decide the action of each round with their strategies from the game state
+//! observed, such as the bond of the round, the fee of their own and the moves of the opponents.
+//!
+//! The scripted and the probabilistic strategies are resolved into choices when the scenario is
+//! imported, and the adaptive strategies react to the game with the rules
+//! - `lie_until_bond`: the relayer lies till the bond of the round is more than X, and the
+//!   challenger challenges till the bond of the round is more than X
+//! - `profitable`: the challenger challenges only if the relayer lies and the reward covers the bond
+//!   and the fee, the relayer submits honestly only if there is the slash of lying opponents to share
+//! - `tit_for_tat`: honest in the first round, and then the relayer lies if any opponent lied in
+//!   the previous round, the challenger challenges if the relayer lied in the previous round
+use std::fmt::Debug;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::scenario::Role;
+
+/// # Strategy Config
+/// The strategy of the participant.  `rounds` is the length of the choice, default the length of
+/// the longest choice of relayers for a relayer, and the length of the relayer choice for a
+/// challenger.
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StrategyConfig {
+    /// The choice is scripted
+    Scripted { choice: String },
+    /// P: the probability to lie in each round, the random numbers are from the `seed` of the
+    /// scenario
+    Probabilistic { P: f64, rounds: Option<usize> },
+    /// The participant reacts to the game with the rule, default `lie_until_bond`,
+    /// X: the bond threshold of `lie_until_bond`
+    Adaptive {
+        rule: Option<Rule>,
+        X: Option<f64>,
+        rounds: Option<usize>,
+    },
+}
+
+/// # Rule
+/// The built-in rules of adaptive strategies
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Rule {
+    LieUntilBond,
+    Profitable,
+    TitForTat,
+}
+
+impl StrategyConfig {
+    pub fn validate(&self) -> Result<(), Error> {
+        match self {
+            StrategyConfig::Probabilistic { P, .. } if *P < 0.0 || *P > 1.0 => Err(
+                Error::ParameterError("the probability to lie should be in [0, 1]"),
+            ),
+            StrategyConfig::Adaptive { rule, X, .. }
+                if rule.unwrap_or(Rule::LieUntilBond) == Rule::LieUntilBond && X.is_none() =>
+            {
+                Err(Error::ParameterError(
+                    "X should be set for the lie_until_bond rule",
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+    /// The strategy reacts to the game or not
+    pub fn is_adaptive(&self) -> bool {
+        matches!(self, StrategyConfig::Adaptive { .. })
+    }
+    /// The rounds of the choice
+    pub fn rounds(&self) -> Option<usize> {
+        match self {
+            StrategyConfig::Scripted { choice } => Some(choice.len()),
+            StrategyConfig::Probabilistic { rounds, .. }
+            | StrategyConfig::Adaptive { rounds, .. } => *rounds,
+        }
+    }
+    /// Build the strategy of the participant in the role with the choice resolved
+    pub fn build(&self, role: Role, choice: &str) -> Box<dyn Strategy> {
+        match self {
+            StrategyConfig::Adaptive { rule, X, .. } => match rule.unwrap_or(Rule::LieUntilBond) {
+                Rule::LieUntilBond => Box::new(LieUntilBond {
+                    role,
+                    bond: X.unwrap_or_default(),
+                }),
+                Rule::Profitable => Box::new(Profitable { role }),
+                Rule::TitForTat => Box::new(TitForTat { role }),
+            },
+            _ => Box::new(Scripted::from(choice)),
+        }
+    }
+}
+
+/// # Action
+/// The action of the participant in a round, the lying challenger challenges the honest relayer
+/// or confirms the lying relayer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Honest,
+    Lie,
+    NoResponse,
+}
+
+/// # Observation
+/// The game state observed by the participant before deciding the action of the round
+#[derive(Debug, Default, Clone)]
+pub struct Observation {
+    /// the submit round, start from 1
+    pub round: usize,
+    /// the bond of the round
+    pub bond: f64,
+    /// the fee of the extrinsic sent by the participant
+    pub fee: f64,
+    /// the reward with the bond returned from the reward equation if the participant challenges the
+    /// lying relayers in this round, only observed by challengers
+    pub reward: f64,
+    /// the submissions of the relayers in this round as (id, lie), only observed by challengers
+    pub moves: Vec<(String, bool)>,
+    /// the moves of the opponents in the previous round as (id, lie)
+    pub previous: Vec<(String, bool)>,
+}
+
+impl Observation {
+    fn opponent_lied(&self) -> bool {
+        self.previous.iter().any(|m| m.1)
+    }
+}
+
+/// # Strategy
+/// Decide the action of each round from the observation
+pub trait Strategy: Debug + StrategyClone {
+    fn decide(&mut self, observation: &Observation) -> Action;
+}
+
+/// Clone the boxed strategy, so the game can be cloned in each round
+pub trait StrategyClone {
+    fn clone_box(&self) -> Box<dyn Strategy>;
+}
+
+impl<T: 'static + Strategy + Clone> StrategyClone for T {
+    fn clone_box(&self) -> Box<dyn Strategy> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Strategy> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// # Scripted
+/// The action of each round is from the choice, the relayer choices are `H`, `L`, `N`, where `N` is
+/// submitted as honest as in the scenario data, and the challenger choices are `1`, `0`, and there
+/// is no response after the choice
+#[derive(Debug, Clone)]
+pub struct Scripted {
+    choice: Vec<char>,
+}
+
+impl From<&str> for Scripted {
+    fn from(choice: &str) -> Self {
+        Scripted {
+            choice: choice.chars().collect(),
+        }
+    }
+}
+
+impl Strategy for Scripted {
+    fn decide(&mut self, o: &Observation) -> Action {
+        match o.round.checked_sub(1).and_then(|i| self.choice.get(i)) {
+            Some('L') => Action::Lie,
+            Some(_) => Action::Honest,
+            None => Action::NoResponse,
+        }
+    }
+}
+
+/// # Lie Until Bond
+/// The relayer lies till the bond is more than the threshold, and the challenger challenges till
+/// the bond is more than the threshold
+#[derive(Debug, Clone)]
+pub struct LieUntilBond {
+    role: Role,
+    bond: f64,
+}
+
+impl Strategy for LieUntilBond {
+    fn decide(&mut self, o: &Observation) -> Action {
+        match (self.role, o.bond <= self.bond) {
+            (Role::Relayer, true) => Action::Lie,
+            (Role::Relayer, false) => Action::Honest,
+            (Role::Challenger, true) => Action::Honest,
+            (Role::Challenger, false) => Action::NoResponse,
+        }
+    }
+}
+
+/// # Profitable
+/// The challenger challenges only if the relayer lies and the reward from the reward equation with
+/// the bond returned covers the bond and the fee, the relayer submits honestly only if there is slash to share
+#[derive(Debug, Clone)]
+pub struct Profitable {
+    role: Role,
+}
+
+impl Strategy for Profitable {
+    fn decide(&mut self, o: &Observation) -> Action {
+        let profitable = match self.role {
+            Role::Relayer => o.round == 1 || o.opponent_lied(),
+            Role::Challenger => o.moves.iter().any(|m| m.1) && o.reward > o.bond + o.fee,
+        };
+        if profitable {
+            Action::Honest
+        } else {
+            Action::NoResponse
+        }
+    }
+}
+
+/// # Tit For Tat
+/// Honest in the first round, and then the relayer lies if any opponent lied in the previous
+/// round, the challenger challenges if the relayer lied in the previous round
+#[derive(Debug, Clone)]
+pub struct TitForTat {
+    role: Role,
+}
+
+impl Strategy for TitForTat {
+    fn decide(&mut self, o: &Observation) -> Action {
+        let retaliate = o.round > 1 && o.opponent_lied();
+        match self.role {
+            Role::Relayer if retaliate => Action::Lie,
+            Role::Relayer => Action::Honest,
+            Role::Challenger if o.round == 1 || retaliate => Action::Honest,
+            Role::Challenger => Action::NoResponse,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn observe(round: usize, bond: f64, previous: Vec<bool>) -> Observation {
+        Observation {
+            round,
+            bond,
+            previous: previous
+                .into_iter()
+                .map(|lie| ("Evil".to_string(), lie))
+                .collect(),
+            ..Default::default()
+        }
+    }
+    #[test]
+    fn test_built_in_strategies() {
+        let mut s = Scripted::from("LHN");
+        let actions: Vec<Action> = (1..=4)
+            .map(|r| s.decide(&observe(r, 0.0, vec![])))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                Action::Lie,
+                Action::Honest,
+                Action::Honest,
+                Action::NoResponse
+            ]
+        );
+
+        let mut s = LieUntilBond {
+            role: Role::Relayer,
+            bond: 20.0,
+        };
+        assert_eq!(s.decide(&observe(1, 20.0, vec![])), Action::Lie);
+        assert_eq!(s.decide(&observe(2, 40.0, vec![])), Action::Honest);
+
+        let mut s = Profitable {
+            role: Role::Challenger,
+        };
+        let mut o = observe(1, 10.0, vec![]);
+        o.moves = vec![("Evil".to_string(), true)];
+        o.reward = 15.0;
+        assert_eq!(s.decide(&o), Action::Honest);
+        o.fee = 5.0;
+        assert_eq!(s.decide(&o), Action::NoResponse);
+        o.moves = vec![("Evil".to_string(), false)];
+        o.fee = 0.0;
+        assert_eq!(s.decide(&o), Action::NoResponse);
+
+        let mut s = TitForTat {
+            role: Role::Relayer,
+        };
+        assert_eq!(s.decide(&observe(1, 10.0, vec![])), Action::Honest);
+        assert_eq!(s.decide(&observe(2, 10.0, vec![true])), Action::Lie);
+        assert_eq!(s.decide(&observe(3, 10.0, vec![false])), Action::Honest);
+        let mut s = TitForTat {
+            role: Role::Challenger,
+        };
+        assert_eq!(s.decide(&observe(1, 10.0, vec![])), Action::Honest);
+        assert_eq!(s.decide(&observe(2, 10.0, vec![false])), Action::NoResponse);
+        assert_eq!(s.decide(&observe(3, 10.0, vec![true])), Action::Honest);
+    }
+}
@@ -0,0 +1,457 @@
+//! Timeline module
+//! Render the relay positions of a game as the diagrams in README, the Ethereum blocks submitted
+//! in each round are labeled with the round on the axis from the genesis `G`, and each participant
+//! has a row showing the choice on each position and the result of the game.
+//! ```text
+//!            G===========2==========1===>
+//! Darwinia               C          H    Reward
+//! Evil                   -          -    Slash
+//! ```
+//! - `H`, `L`: the honest or lie header submitted by the relayer
+//! - `0`, `1`: the challenger disagrees or agrees the header
+//! - `N`, `R`: no response in the challenge window, or the header rejected
+//! - `C`: the block is confirmed
+//! - `-`: the bond is slashed
+//!
+//! The positions are scaled with the width, and are moved right if the labels collide, so the
+//! order of the positions is always kept.
+use std::collections::BTreeMap;
+
+use crate::chain::{ChainsStatus, ParticipatorStatus};
+use crate::game::GameResult;
+use crate::html::escape;
+use crate::scenario::ScenarioConfig;
+
+/// The width used if the width of the terminal is unknown
+static DEFAULT_WIDTH: usize = 100;
+
+/// The pixels of a character in SVG
+static SVG_CHAR_WIDTH: usize = 8;
+static SVG_ROW_HEIGHT: usize = 24;
+
+/// The width of the terminal from the environment variable `COLUMNS`, or the default width
+pub fn terminal_width() -> usize {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse().ok())
+        .filter(|c| *c > 0)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// # Outcome
+/// The result of a participant in the game
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Outcome {
+    Slash,
+    Return,
+    Reward,
+    /// The game is not closed, and the rewards are not settled
+    Pending,
+}
+
+impl Outcome {
+    fn of(status: &ParticipatorStatus, missed: bool, settled: bool) -> Self {
+        let earned = status.reward.0 + status.reward.1 - status.pay;
+        if missed || (settled && earned < -0.000_001) {
+            Outcome::Slash
+        } else if !settled {
+            Outcome::Pending
+        } else if earned > 0.000_001 {
+            Outcome::Reward
+        } else {
+            Outcome::Return
+        }
+    }
+    fn name(self) -> &'static str {
+        match self {
+            Outcome::Slash => "Slash",
+            Outcome::Return => "Return",
+            Outcome::Reward => "Reward",
+            Outcome::Pending => "Pending",
+        }
+    }
+}
+
+/// # Position
+/// The Ethereum block submitted in the rounds
+#[derive(Debug, Clone)]
+pub struct Position {
+    pub block: usize,
+    /// The rounds submitting the block, start from 1
+    pub rounds: Vec<usize>,
+    /// The block is confirmed in any of the rounds
+    pub confirmed: bool,
+}
+
+impl Position {
+    fn label(&self) -> String {
+        let rounds: Vec<String> = self.rounds.iter().map(|r| r.to_string()).collect();
+        rounds.join(",")
+    }
+}
+
+/// # Row
+/// The cells of a participant on the positions
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub name: String,
+    /// The cell on each position, keyed by the index of the position
+    pub cells: BTreeMap<usize, char>,
+    pub outcome: Outcome,
+}
+
+/// # Timeline
+/// The positions and the rows of the participants in a game
+#[derive(Debug, Clone)]
+pub struct Timeline {
+    pub genesis: usize,
+    /// The positions sorted by the block
+    pub positions: Vec<Position>,
+    pub rows: Vec<Row>,
+}
+
+impl Timeline {
+    /// Collect the positions and the choices of the game played with the scenario
+    pub fn new(config: &ScenarioConfig, result: &GameResult, chains_status: &ChainsStatus) -> Self {
+        Self::collect(config, result, chains_status, true)
+    }
+
+    /// Collect the positions and the choices of the game not closed yet, only the bonds slashed
+    /// for no response are known
+    pub fn in_progress(
+        config: &ScenarioConfig,
+        result: &GameResult,
+        chains_status: &ChainsStatus,
+    ) -> Self {
+        Self::collect(config, result, chains_status, false)
+    }
+
+    fn collect(
+        config: &ScenarioConfig,
+        result: &GameResult,
+        chains_status: &ChainsStatus,
+        settled: bool,
+    ) -> Self {
+        let mut blocks: BTreeMap<usize, Position> = BTreeMap::new();
+        for (i, r) in result.rounds.iter().enumerate() {
+            let p = blocks
+                .entry(r.submit_ethereum_block)
+                .or_insert_with(|| Position {
+                    block: r.submit_ethereum_block,
+                    rounds: Vec::new(),
+                    confirmed: false,
+                });
+            p.rounds.push(i + 1);
+            p.confirmed |= !r.submissions.is_empty() && r.submissions.iter().all(|s| !s.1);
+        }
+        let positions: Vec<Position> = blocks.values().cloned().collect();
+        let position_of = |round: usize| {
+            positions
+                .iter()
+                .position(|p| p.rounds.contains(&round))
+                .unwrap_or_default()
+        };
+        let missed = |id: &str| {
+            result
+                .rounds
+                .iter()
+                .any(|r| r.missed.iter().chain(r.rejected.iter()).any(|m| m == id))
+        };
+
+        let mut rows = Vec::new();
+        for relayer in config.relayers.iter() {
+            let status = match chains_status.relayers.get(relayer.id()) {
+                Some(s) => s,
+                None => continue,
+            };
+            let outcome = Outcome::of(status, missed(relayer.id()), settled);
+            let mut cells = BTreeMap::new();
+            for (i, r) in result.rounds.iter().enumerate() {
+                let choice = if let Some(s) = r.submissions.iter().find(|s| s.0 == relayer.id()) {
+                    if s.1 {
+                        'L'
+                    } else {
+                        'H'
+                    }
+                } else if r.missed.iter().any(|m| m == relayer.id()) {
+                    'N'
+                } else if r.rejected.iter().any(|m| m == relayer.id()) {
+                    'R'
+                } else {
+                    continue;
+                };
+                let p = position_of(i + 1);
+                cells.insert(p, cell(choice, 'H', positions[p].confirmed, outcome));
+            }
+            rows.push(Row {
+                name: status.name.clone().unwrap_or_else(|| status.id.clone()),
+                cells,
+                outcome,
+            });
+        }
+        for challenger in config.challengers.iter().flatten() {
+            let status = match chains_status.challengers.get(challenger.id()) {
+                Some(s) => s,
+                None => continue,
+            };
+            let outcome = Outcome::of(status, missed(challenger.id()), settled);
+            // the challenger shows the opinions on all the positions before the last challenge
+            let last = result
+                .rounds
+                .iter()
+                .rposition(|r| {
+                    r.challenges
+                        .iter()
+                        .chain(r.missed.iter())
+                        .any(|c| c == challenger.id())
+                })
+                .map(|i| i + 1)
+                .unwrap_or_default();
+            let mut cells = BTreeMap::new();
+            for (i, choice) in challenger.choice.chars().take(last).enumerate() {
+                let r = &result.rounds[i];
+                let choice = if r.missed.iter().any(|m| m == challenger.id()) {
+                    'N'
+                } else {
+                    choice
+                };
+                let p = position_of(i + 1);
+                cells.insert(p, cell(choice, '1', positions[p].confirmed, outcome));
+            }
+            rows.push(Row {
+                name: status.name.clone().unwrap_or_else(|| status.id.clone()),
+                cells,
+                outcome,
+            });
+        }
+
+        Timeline {
+            genesis: result.relay_positions.geneisis,
+            positions,
+            rows,
+        }
+    }
+
+    /// The columns of the positions from the genesis, scaled into the width of the track and
+    /// moved right to keep a space between the labels
+    fn columns(&self, track: usize) -> Vec<usize> {
+        let max = self
+            .positions
+            .iter()
+            .map(|p| p.block.saturating_sub(self.genesis))
+            .max()
+            .unwrap_or_default()
+            .max(1);
+        let scale = track.saturating_sub(self.positions.last().map_or(1, |p| p.label().len()));
+        let mut columns = Vec::new();
+        let mut next = 2;
+        for p in self.positions.iter() {
+            let c = (p.block.saturating_sub(self.genesis) * scale / max).max(next);
+            next = c + p.label().len() + 1;
+            columns.push(c);
+        }
+        columns
+    }
+
+    /// Render the timeline as text in the width
+    pub fn render(&self, width: usize) -> String {
+        let name_width = self
+            .rows
+            .iter()
+            .map(|r| r.name.len())
+            .max()
+            .unwrap_or_default()
+            + 2;
+        let track = width
+            .saturating_sub(name_width + "===> Pending".len())
+            .max(1);
+        let columns = self.columns(track);
+        let end = columns
+            .iter()
+            .zip(self.positions.iter())
+            .map(|(c, p)| c + p.label().len())
+            .max()
+            .unwrap_or(1)
+            .max(track);
+
+        let mut axis: Vec<char> = vec!['='; end];
+        axis[0] = 'G';
+        for (c, p) in columns.iter().zip(self.positions.iter()) {
+            for (i, ch) in p.label().chars().enumerate() {
+                axis[c + i] = ch;
+            }
+        }
+        let mut output = format!(
+            "{}{}===>\n",
+            " ".repeat(name_width),
+            axis.into_iter().collect::<String>()
+        );
+        for row in self.rows.iter() {
+            let mut line = vec![' '; end];
+            for (p, ch) in row.cells.iter() {
+                line[columns[*p]] = *ch;
+            }
+            output.push_str(&format!(
+                "{:<name_width$}{}     {}\n",
+                row.name,
+                line.into_iter().collect::<String>(),
+                row.outcome.name(),
+                name_width = name_width
+            ));
+        }
+        output.push_str(
+            "H/L: honest/lie, 0/1: disagree/agree, N: no response, R: rejected, C: confirmed, -: slash",
+        );
+        output
+    }
+
+    /// Render the timeline as a SVG document
+    pub fn to_svg(&self) -> String {
+        let name_width = self
+            .rows
+            .iter()
+            .map(|r| r.name.len())
+            .max()
+            .unwrap_or_default()
+            + 2;
+        let columns = self.columns(DEFAULT_WIDTH);
+        let end = columns
+            .iter()
+            .zip(self.positions.iter())
+            .map(|(c, p)| c + p.label().len())
+            .max()
+            .unwrap_or(1)
+            .max(DEFAULT_WIDTH);
+        let x = |column: usize| (name_width + column) * SVG_CHAR_WIDTH;
+        let y = |row: usize| (row + 1) * SVG_ROW_HEIGHT;
+        let width = x(end + 12);
+        let height = y(self.rows.len() + 1);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             font-family=\"monospace\" font-size=\"14\">\n",
+            width, height
+        );
+        svg.push_str(&format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#888\" stroke-width=\"2\"/>\n",
+            x(0),
+            y(0),
+            x(end + 3),
+            y(0)
+        ));
+        svg.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">G</text>\n",
+            x(0),
+            y(0) - 6
+        ));
+        for (c, p) in columns.iter().zip(self.positions.iter()) {
+            let color = if p.confirmed { "#2a2" } else { "#333" };
+            svg.push_str(&format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"4\" fill=\"{}\"><title>Ethereum #{}</title></circle>\n\
+                 <text x=\"{}\" y=\"{}\">{}</text>\n",
+                x(*c),
+                y(0),
+                color,
+                p.block,
+                x(*c),
+                y(0) - 8,
+                p.label()
+            ));
+        }
+        for (i, row) in self.rows.iter().enumerate() {
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\">{}</text>\n",
+                SVG_CHAR_WIDTH,
+                y(i + 1),
+                escape(&row.name)
+            ));
+            for (p, ch) in row.cells.iter() {
+                let color = match ch {
+                    'C' => "#2a2",
+                    '-' => "#d22",
+                    _ => "#333",
+                };
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\" fill=\"{}\" text-anchor=\"middle\">{}</text>\n",
+                    x(columns[*p]),
+                    y(i + 1),
+                    color,
+                    ch
+                ));
+            }
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\">{}</text>\n",
+                x(end + 5),
+                y(i + 1),
+                row.outcome.name()
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// The cell of the choice, the bond slashed is `-`, the honest submission on the confirmed block
+/// is `C`
+fn cell(choice: char, honest: char, confirmed: bool, outcome: Outcome) -> char {
+    if outcome == Outcome::Slash {
+        '-'
+    } else if confirmed && choice == honest {
+        'C'
+    } else {
+        choice
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::run;
+    use std::str::FromStr;
+    #[test]
+    fn test_render() {
+        let config = <ScenarioConfig>::from_str(
+            r#"
+			challenge_function = "10"
+			target_function = "half"
+			bond_function = "10"
+			reward_function = "split"
+
+			[reward_split]
+			P = 1.0
+
+			[[relayers]]
+			name = "Evil"
+			choice = "LLLLLLLLLLLL"
+			"#,
+        )
+        .unwrap();
+        let (chains_status, result) = run(&config).unwrap();
+        let timeline = Timeline::new(&config, &result, &chains_status);
+        assert!(result.rounds.len() > 9);
+        // the rounds submitting the same block are labeled on the same position
+        assert!(timeline.positions.len() < result.rounds.len());
+
+        let text = timeline.render(80);
+        let lines: Vec<&str> = text.lines().collect();
+        // the labels of more than 9 rounds are kept in order without collision
+        let labels: Vec<usize> = lines[0]
+            .split(&['=', ' ', 'G', ','][..])
+            .filter(|l| !l.is_empty() && *l != ">")
+            .map(|l| l.parse().unwrap())
+            .collect();
+        let mut expected: Vec<usize> = (1..=result.rounds.len()).collect();
+        expected.sort_by_key(|r| (result.rounds[r - 1].submit_ethereum_block, *r));
+        assert_eq!(labels, expected);
+        assert!(lines[1].starts_with("Darwinia"));
+        assert!(lines[1].ends_with("Reward"));
+        assert!(lines[2].starts_with("Evil"));
+        assert!(lines[2].ends_with("Slash"));
+        assert_eq!(lines[2].matches('-').count(), timeline.positions.len());
+        assert!(lines.iter().take(3).all(|l| l.len() <= 80));
+
+        let svg = timeline.to_svg();
+        assert_eq!(svg.matches("<circle").count(), timeline.positions.len());
+        assert!(svg.contains(">Slash</text>"));
+    }
+}
@@ -0,0 +1,143 @@
+//! Treasury module
+//! The treasury collects the fee from the redeem actions of users, and pays the relayers when the
+//! reward is from `RewardFrom::Treasure`.  Some portion of slash can be routed into treasury.
+//!
+//! With the treasury, the simulation can tell the treasury is solvent or not under the reward
+//! function, for example, `treasury_last`.
+use std::fmt;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// # Treasury Config
+/// fee income = R * relayed Ethereum blocks
+/// slash income = S * slash value of each submit round
+#[allow(non_snake_case)]
+#[derive(Default, Debug, Deserialize, Serialize, Copy, Clone)]
+pub struct TreasuryConfig {
+    /// R: the fee paid by users' redeem actions for each Ethereum block relayed
+    R: f64,
+    /// S: the portion of slash routed to treasury, the rest is used by reward function
+    #[serde(default)]
+    S: f64,
+    /// I: the initial balance of treasury
+    #[serde(default)]
+    I: f64,
+}
+
+impl TreasuryConfig {
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.R < 0.0 {
+            return Err(Error::field("R", "should not be negative"));
+        }
+        if self.S < 0.0 || self.S > 1.0 {
+            return Err(Error::field("S", "should be between 0 and 1"));
+        }
+        Ok(())
+    }
+}
+
+/// # Treasury
+/// The status of treasury in simulation
+#[derive(Default, Debug, Clone)]
+pub struct Treasury {
+    /// The fee for each Ethereum block relayed
+    pub fee_rate: f64,
+    /// The portion of slash routed to treasury
+    pub slash_portion: f64,
+    /// The current balance, it will be negative when the treasury is insolvent
+    pub balance: f64,
+    /// The total fee income from redeem actions
+    pub fee_income: f64,
+    /// The total income from slash
+    pub slash_income: f64,
+    /// The total value paid to the relayers
+    pub paid: f64,
+}
+
+impl From<TreasuryConfig> for Treasury {
+    fn from(c: TreasuryConfig) -> Self {
+        Treasury {
+            fee_rate: c.R,
+            slash_portion: c.S,
+            balance: c.I,
+            ..Default::default()
+        }
+    }
+}
+
+impl fmt::Display for Treasury {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Treasury: {} (fee: {}, slash: {}, paid: {}) {}",
+            self.balance,
+            self.fee_income,
+            self.slash_income,
+            self.paid,
+            if self.is_solvent() {
+                "solvent"
+            } else {
+                "insolvent"
+            }
+        )
+    }
+}
+
+impl Treasury {
+    /// Collect the fee of the redeem actions for the relayed Ethereum blocks
+    pub fn collect_fee(&mut self, relayed_blocks: usize) {
+        let fee = self.fee_rate * relayed_blocks as f64;
+        self.fee_income += fee;
+        self.balance += fee;
+    }
+    /// Take the portion of slash, and return the rest for the reward function
+    pub fn take_slash(&mut self, slash: f64) -> f64 {
+        let take = self.slash_portion * slash;
+        self.slash_income += take;
+        self.balance += take;
+        slash - take
+    }
+    /// Take the slash left in the bond pool of the closed game
+    pub fn take_unclaimed(&mut self, slash: f64) {
+        self.slash_income += slash;
+        self.balance += slash;
+    }
+    pub fn pay(&mut self, value: f64) {
+        self.paid += value;
+        self.balance -= value;
+    }
+    pub fn is_solvent(&self) -> bool {
+        self.balance >= 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_validate() {
+        let mut c = TreasuryConfig::default();
+        c.R = 0.1;
+        c.S = 0.2;
+        c.I = 30.0;
+        assert!(c.validate().is_ok());
+        c.S = 1.2;
+        assert!(c.validate().is_err());
+    }
+    #[test]
+    fn test_treasury_solvent() {
+        let mut c = TreasuryConfig::default();
+        c.R = 0.5;
+        c.S = 0.5;
+        let mut t: Treasury = c.into();
+        assert_eq!(t.take_slash(10.0), 5.0);
+        t.collect_fee(100);
+        t.pay(50.0);
+        assert!(t.is_solvent());
+        t.pay(10.0);
+        assert!(!t.is_solvent());
+        assert_eq!(t.balance, -5.0);
+    }
+}
@@ -5,40 +5,103 @@ echo "==========================="
 echo ""
 
 echo "======== no patch ==========="
-cargo run -- -v scenario/linear_bond.yml
+cargo run -- -v scenario/linear_bond.toml
 echo ""
 echo "== patch bond_function=1.234 =="
-cargo run -- -p bond_function=1.234 -v scenario/linear_bond.yml
+cargo run -- -p bond_function=1.234 -v scenario/linear_bond.toml
 echo ""
 
 echo "======== no patch ==========="
-cargo run -- -v scenario/basic.yml
+cargo run -- -v scenario/basic.toml
 echo ""
 echo "== patch challenge_function=100 =="
-cargo run -- -p challenge_function=100 -v scenario/linear_bond.yml
+cargo run -- -p challenge_function=100 -v scenario/linear_bond.toml
 echo ""
 
 echo "======== no patch ==========="
-cargo run -- -v scenario/treasury_last.yml
+cargo run -- -v scenario/treasury_last.toml
 echo "== patch reward_split.P=0.7 =="
-cargo run -- -p reward_split.P=0.7 -- scenario/basic.yml
+cargo run -- -p reward_split.P=0.7 -- scenario/basic.toml
 echo ""
 
 echo "======== no patch ==========="
-cargo run -- -v scenario/treasury_last.yml
+cargo run -- -v scenario/treasury_last.toml
 echo "== patch reward_treasury_last.C=90 =="
-cargo run -- -p reward_treasury_last.C=9.0 -- scenario/treasury_last.yml
+cargo run -- -p reward_treasury_last.C=9.0 -- scenario/treasury_last.toml
+echo ""
+
+echo "======== no patch ==========="
+cargo run -- -v scenario/basic.toml
+echo "== patch relayers[0].choice=LLH F=3.5 =="
+cargo run -- -p "relayers[0].choice=LLH" F=3.5 -- scenario/basic.toml
+echo ""
+
+echo "======== charts ==========="
+cargo run -- --plot-dir target/charts scenario/challenger.toml
+cargo run -- --plot-dir target/charts --plot-format png scenario/basic.toml
+cargo run -- --html target/report.html scenario/challenger.toml
+cargo run -- -t --timeline-svg target/timeline.svg scenario/multi-challengers.toml
+echo ""
+
+echo "==============================="
+echo "==== Check scenarios on cli ===="
+echo "==============================="
+echo ""
+cargo run -- check scenario/*.toml
+echo ""
+
+echo "================================="
+echo "====== Analysis subcommands ======="
+echo "================================="
+echo ""
+cargo run -- attack -l 3 -a Evil -p "relayers[1].choice=H" -- scenario/sometimes_lie.toml
+echo ""
+cargo run -- attack scenario/challenger.toml
+echo ""
+cargo run -- equilibrium scenario/sometimes_lie.toml
+echo ""
+cargo run -- equilibrium -l 1 -m scenario/challenger.toml
+echo ""
+cargo run -- optimise -x "bond_linear.C=1..100" -x "challenge_linear.C=1..10" -c "attacker_loss>=100" -c "honest_profit>=0" -- scenario/sometimes_lie.toml
+echo ""
+cargo run -- optimise -m nelder-mead -n 30 -x "reward_split.P=0.1..0.9" -o max:honest_profit -- scenario/sometimes_lie.toml
+echo ""
+cargo run -- sensitivity scenario/sometimes_lie.toml
+echo ""
+cargo run -- sensitivity -d 0.2 scenario/treasury.toml
+echo ""
+cargo run -- compare -b "bond_linear.C=20" -b "relayers[0].choice=LH" -- scenario/sometimes_lie.toml
+echo ""
+cargo run -- compare -j scenario/basic.toml scenario/treasury.toml
+echo ""
+printf 'n\nn\nm Evil H\nb\ne\nq\n' | cargo run -- step scenario/sometimes_lie.toml
 echo ""
 
 echo "==================================="
 echo "==== Test some scenario on cli ===="
 echo "==================================="
 echo ""
-cargo run -- -v scenario/sometimes_lie.yml
+cargo run -- -v scenario/sometimes_lie.toml
+echo ""
+cargo run -- -v scenario/challenger.toml
+echo ""
+cargo run -- -v scenario/multi-challengers.toml
+echo ""
+cargo run -- -v scenario/multi-challengers2.toml
+echo ""
+cargo run -- -v scenario/treasury.toml
+echo ""
+cargo run -- -v scenario/concurrent.toml
+echo ""
+cargo run -- -v scenario/participants.toml
+echo ""
+cargo run -- -v scenario/block_time.toml
+echo ""
+cargo run -- -v scenario/latency.toml
 echo ""
-cargo run -- -v scenario/challenger.yml
+cargo run -- -v scenario/transaction_cost.toml
 echo ""
-cargo run -- -v scenario/multi-challengers.yml
+cargo run -- -v scenario/header.toml
 echo ""
-cargo run -- -v scenario/multi-challengers2.yml
+cargo run -- -v scenario/ethereum_headers.toml
 echo ""